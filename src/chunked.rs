@@ -1,6 +1,9 @@
 use shipyard::{Get, Unique, UniqueView, View, World};
 
-use crate::{components::FieldOfView, gamesym::GameSym, map::Map, player::PlayerId, ui::Options};
+use crate::{
+    components::FieldOfView, gamesym::GameSym, map::Map, player::PlayerId, ui::Options,
+    vision::LightMap,
+};
 use ruggrogue::{
     util::{Color, Position, Size},
     Symbol, TileGrid, Tileset,
@@ -9,6 +12,10 @@ use ruggrogue::{
 pub const CHUNK_TILE_WIDTH: i32 = 8;
 pub const CHUNK_TILE_HEIGHT: i32 = 8;
 
+/// The dimmest that tiles in the player's field of view are shaded, so that dark areas in view
+/// can still be made out.
+const MIN_SHADE: u8 = 80;
+
 #[derive(Unique)]
 pub struct Camera(pub Position);
 
@@ -43,6 +50,7 @@ pub struct ChunkedMapGrid {
     tile_size: Size,
     screen_size: Size,
     dirty_rects: Vec<(Position, Size)>,
+    animation_origin: Position,
 }
 
 impl ChunkedMapGrid {
@@ -55,6 +63,7 @@ impl ChunkedMapGrid {
             tile_size: Size { w: 0, h: 0 },
             screen_size: Size { w: 0, h: 0 },
             dirty_rects: Vec::new(),
+            animation_origin: Position { x: 0, y: 0 },
         }
     }
 
@@ -154,6 +163,23 @@ impl ChunkedMapGrid {
         }
     }
 
+    /// Move animations playing over the grid along with the map when the camera has moved the grid
+    /// onto different map chunks since the last call, so they stay over the map positions they
+    /// were started at.  Call this after [ChunkedMapGrid::draw].
+    pub fn scroll_animations<Y: Symbol>(&mut self, world: &World, grid: &mut TileGrid<Y>) {
+        let top_left_chunk = self.screen_top_left_map_chunk(world);
+        let origin = Position {
+            x: top_left_chunk.x * CHUNK_TILE_WIDTH,
+            y: top_left_chunk.y * CHUNK_TILE_HEIGHT,
+        };
+
+        grid.shift_animations((
+            self.animation_origin.x - origin.x,
+            self.animation_origin.y - origin.y,
+        ));
+        self.animation_origin = origin;
+    }
+
     /// Convert a map position into a grid position.
     pub fn map_to_grid_pos(&self, world: &World, map_pos: Position) -> Option<Position> {
         let top_left_chunk = self.screen_top_left_map_chunk(world);
@@ -262,6 +288,7 @@ impl ChunkedMapGrid {
         }
 
         let map = world.borrow::<UniqueView<Map>>().unwrap();
+        let light_map = world.borrow::<UniqueView<LightMap>>().unwrap();
         let fovs = world.borrow::<View<FieldOfView>>().unwrap();
         let player_fov = {
             let player_id = world.borrow::<UniqueView<PlayerId>>().unwrap();
@@ -279,7 +306,15 @@ impl ChunkedMapGrid {
                 ) {
                     if let Some((sym, color)) = tile {
                         let color = if player_fov.get((tx, ty)) {
-                            color
+                            // Shade tiles by how well they're lit.
+                            let shade = light_map.get((tx, ty)).max(MIN_SHADE) as u16;
+                            let scale = |c: u8| (c as u16 * shade / 255) as u8;
+
+                            Color {
+                                r: scale(color.r),
+                                g: scale(color.g),
+                                b: scale(color.b),
+                            }
                         } else {
                             let v =
                                 ((color.r as i32 * 30 + color.g as i32 * 59 + color.b as i32 * 11)
@@ -151,6 +151,13 @@ pub struct Inventory {
 #[derive(Component, Deserialize, Serialize)]
 pub struct Item;
 
+/// Casts light of the given `intensity` out to `range` tiles away, getting dimmer with distance.
+#[derive(Component, Deserialize, Serialize)]
+pub struct LightSource {
+    pub range: i32,
+    pub intensity: u8,
+}
+
 #[derive(Component, Deserialize, Serialize)]
 pub struct Monster;
 
@@ -12,11 +12,13 @@ use crate::{
         Asleep, BlocksTile, CombatBonus, CombatStats, Coord, Equipment, Experience,
         GivesExperience, HurtBy, Name, Tally,
     },
+    effects::{Effect, Effects},
     magicnum,
     map::Map,
     message::Messages,
     player::{PlayerAlive, PlayerId},
-    saveload, spawn, GameSeed, TurnCount,
+    saveload::{self, SaveSlot},
+    spawn, ui, GameSeed, TurnCount,
 };
 
 pub fn melee_attack(
@@ -28,6 +30,7 @@ pub fn melee_attack(
     mut combat_stats: ViewMut<CombatStats>,
     mut hurt_bys: ViewMut<HurtBy>,
     mut tallies: ViewMut<Tally>,
+    mut effects: UniqueViewMut<Effects>,
     (asleeps, combat_bonuses, equipments, names, coords): (
         View<Asleep>,
         View<CombatBonus>,
@@ -114,9 +117,15 @@ pub fn melee_attack(
         if let Ok(def_tally) = (&mut tallies).get(defender) {
             def_tally.damage_taken += damage.max(0) as u64;
         }
-        msgs.add(format!(
-            "{} hits {} for {} hp{}",
-            att_name, def_name, damage, suffix
+        if let Ok(def_coord) = coords.get(defender) {
+            effects.add(Effect::flash(&[(def_coord.0.into(), ui::DAMAGE_FG)]));
+        }
+        msgs.add_markup(format!(
+            "{} hits {} for {}{}",
+            ruggrogue::escape_markup(att_name),
+            ruggrogue::escape_markup(def_name),
+            ruggrogue::colored(&format!("{} hp", damage), ui::DAMAGE_FG),
+            suffix,
         ));
     } else {
         msgs.add(format!(
@@ -182,7 +191,9 @@ pub fn handle_dead_entities(mut all_storages: AllStoragesViewMut) {
                     },
                 );
 
-                saveload::delete_save_file();
+                saveload::retire_save_slot(
+                    &all_storages.borrow::<UniqueView<SaveSlot>>().unwrap().0,
+                );
 
                 // Don't handle any more dead entities.
                 num_entities = 0;
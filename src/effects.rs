@@ -0,0 +1,136 @@
+use shipyard::{Get, Unique, UniqueView, UniqueViewMut, View, World};
+
+use crate::{chunked::ChunkedMapGrid, components::FieldOfView, gamesym::GameSym, player::PlayerId};
+use ruggrogue::{
+    util::{Color, Position},
+    AnimationCell, AnimationGlyph, TileAnimation, TileGrid,
+};
+
+/// Displayed frames that each ring of a burst lasts.
+const BURST_TICKS: usize = 2;
+
+/// Displayed frames that a flash lasts.
+const FLASH_TICKS: usize = 6;
+
+/// A cell of an effect frame: map position, character to show or `None` to keep what's there, and
+/// foreground and background colors, again with `None` to keep what's there.
+type EffectCell = ((i32, i32), Option<char>, Option<Color>, Option<Color>);
+
+/// A brief visual effect over the map, made of frames that each last one displayed frame.
+pub struct Effect {
+    frames: Vec<Vec<EffectCell>>,
+}
+
+impl Effect {
+    /// A projectile flying along a path of map positions, one tile per frame.
+    pub fn projectile(path: &[(i32, i32)], color: Color) -> Self {
+        Self {
+            frames: path
+                .iter()
+                .map(|&pos| vec![(pos, Some('*'), Some(color), None)])
+                .collect(),
+        }
+    }
+
+    /// A burst of color spreading out from its center over the given map positions.
+    pub fn burst(center: (i32, i32), tiles: &[(i32, i32)], color: Color) -> Self {
+        let ring = |&(x, y): &(i32, i32)| {
+            let (dx, dy) = (x - center.0, y - center.1);
+            ((dx * dx + dy * dy) as f32).sqrt().round() as usize
+        };
+        let num_rings = tiles.iter().map(ring).max().map_or(0, |r| r + 1);
+
+        Self {
+            frames: (0..num_rings * BURST_TICKS)
+                .map(|i| {
+                    tiles
+                        .iter()
+                        .filter(|pos| ring(pos) <= i / BURST_TICKS)
+                        .map(|&pos| (pos, None, None, Some(color)))
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+
+    /// A flash of background color over each of the given map positions at once.
+    pub fn flash(tiles: &[((i32, i32), Color)]) -> Self {
+        let frame: Vec<EffectCell> = tiles
+            .iter()
+            .map(|&(pos, color)| (pos, None, None, Some(color)))
+            .collect();
+
+        Self {
+            frames: vec![frame; if tiles.is_empty() { 0 } else { FLASH_TICKS }],
+        }
+    }
+
+    /// Play another effect after this one.
+    pub fn then(mut self, next: Effect) -> Self {
+        self.frames.extend(next.frames);
+        self
+    }
+}
+
+/// Visual effects waiting to be played over the map by the dungeon mode.
+#[derive(Unique)]
+pub struct Effects(Vec<Effect>);
+
+impl Effects {
+    pub fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    pub fn add(&mut self, effect: Effect) {
+        if !effect.frames.is_empty() {
+            self.0.push(effect);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+}
+
+/// Start playing queued effects as animations over the map grid, leaving out the parts that the
+/// player can't see.
+pub fn play_effects(
+    chunked_map_grid: &ChunkedMapGrid,
+    world: &World,
+    grid: &mut TileGrid<GameSym>,
+) {
+    let mut effects = world.borrow::<UniqueViewMut<Effects>>().unwrap();
+
+    if effects.0.is_empty() {
+        return;
+    }
+
+    let player_id = world.borrow::<UniqueView<PlayerId>>().unwrap();
+    let fovs = world.borrow::<View<FieldOfView>>().unwrap();
+    let fov = fovs.get(player_id.0).unwrap();
+
+    for effect in effects.0.drain(..) {
+        let frames = effect
+            .frames
+            .into_iter()
+            .map(|frame| {
+                frame
+                    .into_iter()
+                    .filter(|(pos, ..)| fov.get(*pos))
+                    .filter_map(|(pos, ch, fg, bg)| {
+                        chunked_map_grid
+                            .map_to_grid_pos(world, Position::from(pos))
+                            .map(|grid_pos| AnimationCell {
+                                pos: grid_pos,
+                                glyph: ch.map(AnimationGlyph::Char),
+                                fg,
+                                bg,
+                            })
+                    })
+                    .collect()
+            })
+            .collect();
+
+        grid.animate(TileAnimation::new(frames, 1));
+    }
+}
@@ -1,5 +1,26 @@
-use sdl2::keyboard::Keycode;
+use sdl2::{
+    controller::{Axis, Button},
+    keyboard::Keycode,
+};
+use serde::{Deserialize, Serialize};
+use shipyard::{Unique, UniqueView, World};
+use std::{
+    error,
+    fs::File,
+    io::{BufReader, BufWriter, ErrorKind, Write},
+};
 
+#[cfg(target_os = "emscripten")]
+use crate::ruggrogue_sync_idbfs;
+use ruggrogue::{InputBuffer, InputEvent, KeyMods};
+
+#[cfg(target_os = "emscripten")]
+const KEY_BINDINGS_FILENAME: &str = "/ruggrogue/keybindings.json";
+
+#[cfg(not(target_os = "emscripten"))]
+const KEY_BINDINGS_FILENAME: &str = "keybindings.json";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
 pub enum GameKey {
     Unmapped,
     Up,
@@ -25,40 +46,418 @@ pub enum GameKey {
     RemoveItem,
     UseItem,
     DropItem,
+    Screenshot,
+    TextScreenshot,
+}
+
+/// A key, along with any modifier keys that must be held with it, bound to a game key.
+#[derive(Clone, Copy, Deserialize, Serialize)]
+pub struct KeyBinding {
+    #[serde(with = "keycode_name")]
+    pub key: Keycode,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub shift: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub ctrl: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub alt: bool,
+    pub game_key: GameKey,
+}
+
+impl KeyBinding {
+    fn new(key: Keycode, game_key: GameKey) -> Self {
+        Self {
+            key,
+            shift: false,
+            ctrl: false,
+            alt: false,
+            game_key,
+        }
+    }
+
+    fn shift(key: Keycode, game_key: GameKey) -> Self {
+        Self {
+            key,
+            shift: true,
+            ctrl: false,
+            alt: false,
+            game_key,
+        }
+    }
+
+    fn has_mods(&self) -> bool {
+        self.shift || self.ctrl || self.alt
+    }
+
+    /// Describe the key and its modifiers for display, e.g. "Shift+.".
+    pub fn describe(&self) -> String {
+        format!(
+            "{}{}{}{}",
+            if self.ctrl { "Ctrl+" } else { "" },
+            if self.alt { "Alt+" } else { "" },
+            if self.shift { "Shift+" } else { "" },
+            self.key.name(),
+        )
+    }
+}
+
+/// Game keys needed to get around menus, including the way back to the key bindings screen, so
+/// they always keep at least one binding.
+const MENU_GAME_KEYS: [GameKey; 4] = [
+    GameKey::Up,
+    GameKey::Down,
+    GameKey::Confirm,
+    GameKey::Cancel,
+];
+
+fn default_key_bindings() -> Vec<KeyBinding> {
+    vec![
+        KeyBinding::new(Keycode::Up, GameKey::Up),
+        KeyBinding::new(Keycode::K, GameKey::Up),
+        KeyBinding::new(Keycode::Kp8, GameKey::Up),
+        KeyBinding::new(Keycode::Down, GameKey::Down),
+        KeyBinding::new(Keycode::J, GameKey::Down),
+        KeyBinding::new(Keycode::Kp2, GameKey::Down),
+        KeyBinding::new(Keycode::Left, GameKey::Left),
+        KeyBinding::new(Keycode::H, GameKey::Left),
+        KeyBinding::new(Keycode::Kp4, GameKey::Left),
+        KeyBinding::new(Keycode::Right, GameKey::Right),
+        KeyBinding::new(Keycode::L, GameKey::Right),
+        KeyBinding::new(Keycode::Kp6, GameKey::Right),
+        KeyBinding::new(Keycode::Y, GameKey::UpLeft),
+        KeyBinding::new(Keycode::Kp7, GameKey::UpLeft),
+        KeyBinding::new(Keycode::U, GameKey::UpRight),
+        KeyBinding::new(Keycode::Kp9, GameKey::UpRight),
+        KeyBinding::new(Keycode::B, GameKey::DownLeft),
+        KeyBinding::new(Keycode::Kp1, GameKey::DownLeft),
+        KeyBinding::new(Keycode::N, GameKey::DownRight),
+        KeyBinding::new(Keycode::Kp3, GameKey::DownRight),
+        KeyBinding::new(Keycode::PageUp, GameKey::PageUp),
+        KeyBinding::new(Keycode::PageDown, GameKey::PageDown),
+        KeyBinding::new(Keycode::Home, GameKey::Home),
+        KeyBinding::new(Keycode::End, GameKey::End),
+        KeyBinding::new(Keycode::Kp5, GameKey::Wait),
+        KeyBinding::new(Keycode::Space, GameKey::Wait),
+        KeyBinding::new(Keycode::Period, GameKey::Wait),
+        KeyBinding::shift(Keycode::Period, GameKey::Descend),
+        KeyBinding::new(Keycode::Greater, GameKey::Descend),
+        KeyBinding::new(Keycode::KpGreater, GameKey::Descend),
+        KeyBinding::new(Keycode::Escape, GameKey::Cancel),
+        KeyBinding::new(Keycode::Return, GameKey::Confirm),
+        KeyBinding::new(Keycode::KpEnter, GameKey::Confirm),
+        KeyBinding::new(Keycode::V, GameKey::ViewMap),
+        KeyBinding::new(Keycode::Comma, GameKey::PickUp),
+        KeyBinding::new(Keycode::G, GameKey::PickUp),
+        KeyBinding::new(Keycode::I, GameKey::Inventory),
+        KeyBinding::new(Keycode::E, GameKey::EquipItem),
+        KeyBinding::new(Keycode::W, GameKey::EquipItem),
+        KeyBinding::new(Keycode::R, GameKey::RemoveItem),
+        KeyBinding::new(Keycode::A, GameKey::UseItem),
+        KeyBinding::new(Keycode::D, GameKey::DropItem),
+        KeyBinding::new(Keycode::F12, GameKey::Screenshot),
+        KeyBinding::shift(Keycode::F12, GameKey::TextScreenshot),
+    ]
+}
+
+/// Serialize keycodes by their SDL names, e.g. "Left", "Keypad 8" or "A".
+mod keycode_name {
+    use sdl2::keyboard::Keycode;
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(key: &Keycode, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&key.name())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Keycode, D::Error> {
+        let name = String::deserialize(deserializer)?;
+
+        Keycode::from_name(&name)
+            .ok_or_else(|| de::Error::custom(format!("unknown key name: {:?}", name)))
+    }
 }
 
-pub fn from_keycode(key: Keycode, shift: bool) -> GameKey {
-    match key {
-        Keycode::Up | Keycode::K | Keycode::Kp8 => GameKey::Up,
-        Keycode::Down | Keycode::J | Keycode::Kp2 => GameKey::Down,
-        Keycode::Left | Keycode::H | Keycode::Kp4 => GameKey::Left,
-        Keycode::Right | Keycode::L | Keycode::Kp6 => GameKey::Right,
-        Keycode::Y | Keycode::Kp7 => GameKey::UpLeft,
-        Keycode::U | Keycode::Kp9 => GameKey::UpRight,
-        Keycode::B | Keycode::Kp1 => GameKey::DownLeft,
-        Keycode::N | Keycode::Kp3 => GameKey::DownRight,
-        Keycode::PageUp => GameKey::PageUp,
-        Keycode::PageDown => GameKey::PageDown,
-        Keycode::Home => GameKey::Home,
-        Keycode::End => GameKey::End,
-        Keycode::Kp5 | Keycode::Space => GameKey::Wait,
-        Keycode::Period => {
-            if shift {
-                GameKey::Descend
-            } else {
-                GameKey::Wait
+/// The table that maps keys to game keys, loaded from and saved to a JSON file so players can
+/// rebind keys.
+#[derive(Unique)]
+pub struct KeyBindings(Vec<KeyBinding>);
+
+impl KeyBindings {
+    /// Load key bindings from the key bindings file, falling back to the defaults if the file
+    /// doesn't exist or can't be read.
+    pub fn load() -> Self {
+        match Self::load_file() {
+            Ok(key_bindings) => key_bindings,
+            Err(e) => {
+                eprintln!("Warning: gamekey::KeyBindings::load: {}", e);
+                Self::default()
+            }
+        }
+    }
+
+    fn load_file() -> Result<Self, Box<dyn error::Error>> {
+        match File::open(KEY_BINDINGS_FILENAME) {
+            Ok(file) => {
+                let mut key_bindings = Self(serde_json::from_reader(BufReader::new(file))?);
+                key_bindings.restore_menu_keys();
+                Ok(key_bindings)
+            }
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Save key bindings to the key bindings file.
+    pub fn save(&self) -> Result<(), Box<dyn error::Error>> {
+        let mut writer = BufWriter::new(File::create(KEY_BINDINGS_FILENAME)?);
+
+        serde_json::to_writer_pretty(&mut writer, &self.0)?;
+        writeln!(writer)?;
+        writer.flush()?;
+
+        #[cfg(target_os = "emscripten")]
+        unsafe {
+            ruggrogue_sync_idbfs();
+        }
+
+        Ok(())
+    }
+
+    /// Find the game key bound to a key with the given modifiers held.  A binding whose modifiers
+    /// match exactly takes priority over a plain binding of the same key.
+    pub fn lookup(&self, key: Keycode, shift: bool, ctrl: bool, alt: bool) -> GameKey {
+        self.0
+            .iter()
+            .find(|b| b.key == key && b.shift == shift && b.ctrl == ctrl && b.alt == alt)
+            .or_else(|| self.0.iter().find(|b| b.key == key && !b.has_mods()))
+            .map_or(GameKey::Unmapped, |b| b.game_key)
+    }
+
+    /// Iterate over the bindings for a game key.
+    pub fn bindings_for(&self, game_key: GameKey) -> impl Iterator<Item = &KeyBinding> {
+        self.0.iter().filter(move |b| b.game_key == game_key)
+    }
+
+    /// Give menu game keys that have no bindings their default bindings back.
+    fn restore_menu_keys(&mut self) {
+        for game_key in MENU_GAME_KEYS {
+            if self.bindings_for(game_key).next().is_none() {
+                self.0.extend(
+                    default_key_bindings()
+                        .into_iter()
+                        .filter(|b| b.game_key == game_key),
+                );
+            }
+        }
+    }
+
+    /// Bind a key with modifiers to a game key, replacing any existing binding of that exact key
+    /// and modifier combination.  Returns false without binding anything if that would take away
+    /// the last binding of a menu game key.
+    pub fn bind(&mut self, binding: KeyBinding) -> bool {
+        let same_keys = |b: &KeyBinding| {
+            b.key == binding.key
+                && b.shift == binding.shift
+                && b.ctrl == binding.ctrl
+                && b.alt == binding.alt
+        };
+
+        if let Some(replaced) = self.0.iter().find(|b| same_keys(b)) {
+            if replaced.game_key != binding.game_key
+                && MENU_GAME_KEYS.contains(&replaced.game_key)
+                && self.bindings_for(replaced.game_key).count() == 1
+            {
+                return false;
             }
         }
-        Keycode::Greater | Keycode::KpGreater => GameKey::Descend,
-        Keycode::Escape => GameKey::Cancel,
-        Keycode::Return | Keycode::KpEnter => GameKey::Confirm,
-        Keycode::V => GameKey::ViewMap,
-        Keycode::Comma | Keycode::G => GameKey::PickUp,
-        Keycode::I => GameKey::Inventory,
-        Keycode::E | Keycode::W => GameKey::EquipItem,
-        Keycode::R => GameKey::RemoveItem,
-        Keycode::A => GameKey::UseItem,
-        Keycode::D => GameKey::DropItem,
+
+        self.0.retain(|b| !same_keys(b));
+        self.0.push(binding);
+        true
+    }
+
+    /// Remove all bindings for a game key, except that menu game keys keep their first binding so
+    /// the menus can still be used.
+    pub fn unbind_all(&mut self, game_key: GameKey) {
+        let keep = if MENU_GAME_KEYS.contains(&game_key) {
+            self.0.iter().position(|b| b.game_key == game_key)
+        } else {
+            None
+        };
+
+        let mut i = 0;
+        self.0.retain(|b| {
+            let kept = b.game_key != game_key || Some(i) == keep;
+            i += 1;
+            kept
+        });
+    }
+
+    /// Restore the default key bindings.
+    pub fn reset(&mut self) {
+        self.0 = default_key_bindings();
+    }
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self(default_key_bindings())
+    }
+}
+
+pub fn from_controller_button(button: Button) -> GameKey {
+    match button {
+        Button::DPadUp => GameKey::Up,
+        Button::DPadDown => GameKey::Down,
+        Button::DPadLeft => GameKey::Left,
+        Button::DPadRight => GameKey::Right,
+        Button::LeftShoulder => GameKey::PageUp,
+        Button::RightShoulder => GameKey::PageDown,
+        Button::A => GameKey::Confirm,
+        Button::B | Button::Start => GameKey::Cancel,
+        Button::X => GameKey::Wait,
+        Button::Y => GameKey::Inventory,
+        Button::Back => GameKey::ViewMap,
+        Button::LeftStick => GameKey::PickUp,
+        Button::RightStick => GameKey::Descend,
         _ => GameKey::Unmapped,
     }
 }
+
+/// Map a game controller axis pushed out of its dead zone to a key; centered axes map to nothing.
+pub fn from_controller_axis(axis: Axis, value: i16) -> Option<GameKey> {
+    let positive = value > 0;
+
+    if value.unsigned_abs() < ruggrogue::AXIS_DEAD_ZONE as u16 {
+        None
+    } else {
+        Some(match axis {
+            Axis::LeftX | Axis::RightX if positive => GameKey::Right,
+            Axis::LeftX | Axis::RightX => GameKey::Left,
+            Axis::LeftY | Axis::RightY if positive => GameKey::Down,
+            Axis::LeftY | Axis::RightY => GameKey::Up,
+            Axis::TriggerLeft => GameKey::Home,
+            Axis::TriggerRight => GameKey::End,
+        })
+    }
+}
+
+/// Get the key for the current input in the input buffer if it's a key or controller press.
+/// Keyboard keys are mapped through the [KeyBindings] in the world.
+pub fn from_input(world: &World, inputs: &InputBuffer) -> Option<GameKey> {
+    match inputs.get_input() {
+        Some(InputEvent::Press(keycode)) => {
+            Some(world.borrow::<UniqueView<KeyBindings>>().unwrap().lookup(
+                keycode,
+                inputs.get_mods(KeyMods::SHIFT),
+                inputs.get_mods(KeyMods::CTRL),
+                inputs.get_mods(KeyMods::ALT),
+            ))
+        }
+        Some(InputEvent::ControllerPress(button)) => Some(from_controller_button(button)),
+        Some(InputEvent::ControllerAxis(axis, value)) => from_controller_axis(axis, value),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ruggrogue::AXIS_DEAD_ZONE;
+
+    #[test]
+    fn clearing_menu_keys_keeps_one_binding() {
+        let mut key_bindings = KeyBindings::default();
+
+        key_bindings.unbind_all(GameKey::Wait);
+        assert_eq!(key_bindings.bindings_for(GameKey::Wait).count(), 0);
+
+        for game_key in MENU_GAME_KEYS {
+            key_bindings.unbind_all(game_key);
+            assert_eq!(key_bindings.bindings_for(game_key).count(), 1);
+        }
+    }
+
+    #[test]
+    fn rebinding_cannot_take_the_last_menu_key() {
+        let mut key_bindings = KeyBindings::default();
+
+        key_bindings.unbind_all(GameKey::Confirm);
+        let last_key = key_bindings
+            .bindings_for(GameKey::Confirm)
+            .next()
+            .unwrap()
+            .key;
+
+        assert!(!key_bindings.bind(KeyBinding::new(last_key, GameKey::Wait)));
+        assert_eq!(
+            key_bindings.lookup(last_key, false, false, false),
+            GameKey::Confirm
+        );
+
+        assert!(key_bindings.bind(KeyBinding::new(Keycode::Space, GameKey::Confirm)));
+        assert!(key_bindings.bind(KeyBinding::new(last_key, GameKey::Wait)));
+        assert_eq!(
+            key_bindings.lookup(last_key, false, false, false),
+            GameKey::Wait
+        );
+    }
+
+    #[test]
+    fn missing_menu_keys_are_restored() {
+        let mut key_bindings = KeyBindings(vec![KeyBinding::new(Keycode::Z, GameKey::Wait)]);
+
+        key_bindings.restore_menu_keys();
+
+        for game_key in MENU_GAME_KEYS {
+            assert!(key_bindings.bindings_for(game_key).next().is_some());
+        }
+        assert_eq!(
+            key_bindings.lookup(Keycode::Return, false, false, false),
+            GameKey::Confirm
+        );
+    }
+
+    #[test]
+    fn controller_buttons_map_to_game_keys() {
+        assert_eq!(from_controller_button(Button::DPadUp), GameKey::Up);
+        assert_eq!(from_controller_button(Button::DPadDown), GameKey::Down);
+        assert_eq!(from_controller_button(Button::DPadLeft), GameKey::Left);
+        assert_eq!(from_controller_button(Button::DPadRight), GameKey::Right);
+        assert_eq!(from_controller_button(Button::A), GameKey::Confirm);
+        assert_eq!(from_controller_button(Button::B), GameKey::Cancel);
+        assert_eq!(from_controller_button(Button::Start), GameKey::Cancel);
+        assert_eq!(from_controller_button(Button::Guide), GameKey::Unmapped);
+    }
+
+    #[test]
+    fn controller_axes_map_to_directions_outside_dead_zone() {
+        assert_eq!(from_controller_axis(Axis::LeftX, 0), None);
+        assert_eq!(from_controller_axis(Axis::LeftX, AXIS_DEAD_ZONE - 1), None);
+        assert_eq!(from_controller_axis(Axis::LeftY, 1 - AXIS_DEAD_ZONE), None);
+
+        assert_eq!(
+            from_controller_axis(Axis::LeftX, AXIS_DEAD_ZONE),
+            Some(GameKey::Right)
+        );
+        assert_eq!(
+            from_controller_axis(Axis::RightX, -AXIS_DEAD_ZONE),
+            Some(GameKey::Left)
+        );
+        assert_eq!(
+            from_controller_axis(Axis::LeftY, i16::MAX),
+            Some(GameKey::Down)
+        );
+        assert_eq!(
+            from_controller_axis(Axis::RightY, i16::MIN),
+            Some(GameKey::Up)
+        );
+        assert_eq!(
+            from_controller_axis(Axis::TriggerLeft, i16::MAX),
+            Some(GameKey::Home)
+        );
+        assert_eq!(
+            from_controller_axis(Axis::TriggerRight, i16::MAX),
+            Some(GameKey::End)
+        );
+    }
+}
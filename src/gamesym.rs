@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, path::PathBuf};
+use std::collections::HashMap;
 
-use ruggrogue::{Symbol, TilesetInfo};
+use ruggrogue::{Symbol, TilesetImage, TilesetInfo};
 
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Deserialize, Serialize)]
 pub enum GameSym {
@@ -158,7 +158,7 @@ impl Symbol for GameSym {
     }
 }
 
-pub fn urizen_tileset_info() -> TilesetInfo<GameSym> {
+pub fn urizen_tileset_info(image: TilesetImage) -> TilesetInfo<GameSym> {
     let mut font_map: HashMap<char, (i32, i32)> = HashMap::new();
     {
         for (i, ch) in ('A'..='T').enumerate() {
@@ -295,7 +295,7 @@ pub fn urizen_tileset_info() -> TilesetInfo<GameSym> {
     }
 
     TilesetInfo::<GameSym> {
-        image_path: PathBuf::from("assets/urizen/urizen-onebit-tileset-mono.png"),
+        image,
         tile_size: (12, 12).into(),
         tile_start: (1, 1).into(),
         tile_gap: (1, 1).into(),
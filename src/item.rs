@@ -6,12 +6,13 @@ use std::cmp::Ordering;
 
 use crate::{
     components::*,
+    effects::{Effect, Effects},
     map::Map,
     message::Messages,
     player::{self, PlayerId},
-    saveload, Wins,
+    saveload, ui, Wins,
 };
-use ruggrogue::FovShape;
+use ruggrogue::{util::Color, FovShape};
 
 #[derive(Unique)]
 pub struct PickUpHint(pub bool);
@@ -340,6 +341,7 @@ pub fn use_item(
         let nutritions = world.borrow::<View<Nutrition>>().unwrap();
         let players = world.borrow::<View<Player>>().unwrap();
         let provides_healings = world.borrow::<View<ProvidesHealing>>().unwrap();
+        let renderables = world.borrow::<View<Renderable>>().unwrap();
         let mut stomachs = world.borrow::<ViewMut<Stomach>>().unwrap();
         let mut tallies = world.borrow::<ViewMut<Tally>>().unwrap();
 
@@ -352,10 +354,14 @@ pub fn use_item(
         let user_name = &names.get(user_id).unwrap().0;
         let item_name = &names.get(item_id).unwrap().0;
 
+        let item_color = renderables.get(item_id).map_or(Color::WHITE, |r| r.fg);
+        let mut flashes = Vec::new();
+
         msgs.add(format!("{} uses {}.", user_name, item_name));
 
         for target_id in targets {
             let target_name = &names.get(target_id).unwrap().0;
+            let target_pos = coords.get(target_id).unwrap().0.into();
 
             if let Ok(stomach) = (&mut stomachs).get(target_id) {
                 if let Ok(nutrition) = nutritions.get(item_id) {
@@ -367,23 +373,30 @@ pub fn use_item(
                 if let Ok(ProvidesHealing { heal_amount }) = provides_healings.get(item_id) {
                     if stats.hp < stats.max_hp {
                         stats.hp = (stats.hp + heal_amount).min(stats.max_hp);
-                        msgs.add(format!(
-                            "{} heals {} for {} hp.",
-                            item_name, target_name, heal_amount,
+                        flashes.push((target_pos, ui::HEAL_FG));
+                        msgs.add_markup(format!(
+                            "{} heals {} for {}.",
+                            ruggrogue::escape_markup(item_name),
+                            ruggrogue::escape_markup(target_name),
+                            ruggrogue::colored(&format!("{} hp", heal_amount), ui::HEAL_FG),
                         ));
                     } else {
                         let amount = 2;
                         stats.hp += amount;
                         stats.max_hp += amount;
-                        msgs.add(format!(
-                            "{} grants {} max hp to {}.",
-                            item_name, amount, target_name,
+                        flashes.push((target_pos, ui::HEAL_FG));
+                        msgs.add_markup(format!(
+                            "{} grants {} to {}.",
+                            ruggrogue::escape_markup(item_name),
+                            ruggrogue::colored(&format!("{} max hp", amount), ui::HEAL_FG),
+                            ruggrogue::escape_markup(target_name),
                         ));
                     }
                 }
 
                 if let Ok(InflictsDamage { damage }) = inflicts_damages.get(item_id) {
                     stats.hp -= damage;
+                    flashes.push((target_pos, ui::DAMAGE_FG));
                     entities.add_component(target_id, &mut hurt_bys, HurtBy::Someone(user_id));
                     if let Ok(user_tally) = (&mut tallies).get(user_id) {
                         user_tally.damage_dealt += *damage.max(&0) as u64;
@@ -391,9 +404,11 @@ pub fn use_item(
                     if let Ok(target_tally) = (&mut tallies).get(target_id) {
                         target_tally.damage_taken += *damage.max(&0) as u64;
                     }
-                    msgs.add(format!(
-                        "{} hits {} for {} hp.",
-                        item_name, target_name, damage,
+                    msgs.add_markup(format!(
+                        "{} hits {} for {}.",
+                        ruggrogue::escape_markup(item_name),
+                        ruggrogue::escape_markup(target_name),
+                        ruggrogue::colored(&format!("{} hp", damage), ui::DAMAGE_FG),
                     ));
                 }
 
@@ -410,6 +425,29 @@ pub fn use_item(
                 }
             }
         }
+
+        // Show the item flying to its target, bursting over its area and hitting or healing.
+        let path: Vec<(i32, i32)> = if target.is_some() {
+            ruggrogue::line_of_fire(&*map, coords.get(user_id).unwrap().0.into(), center).collect()
+        } else {
+            Vec::new()
+        };
+        let mut effect = Effect::projectile(&path, item_color);
+
+        if radius > 0 {
+            let tiles: Vec<(i32, i32)> =
+                ruggrogue::field_of_view(&*map, center, radius, FovShape::CirclePlus)
+                    .filter(|(_, _, symmetric)| *symmetric)
+                    .map(|(x, y, _)| (x, y))
+                    .collect();
+
+            effect = effect.then(Effect::burst(center, &tiles, item_color));
+        }
+
+        world
+            .borrow::<UniqueViewMut<Effects>>()
+            .unwrap()
+            .add(effect.then(Effect::flash(&flashes)));
     }
 
     if world
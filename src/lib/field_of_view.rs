@@ -9,12 +9,102 @@ pub enum FovShape {
     /// Circular FOV extended by half a space to round out the cardinal bumps, though the
     /// additional tiles will not strictly be within range.
     CirclePlus,
+    /// The part of a [FovShape::CirclePlus] FOV within `half_angle` degrees of the direction given
+    /// by `dir`, e.g. `(1, 0)` to face right or `(-1, 1)` to face down and to the left.
+    Cone { dir: (i32, i32), half_angle: i32 },
 }
 
 /// A map-like trait that can be sent into [field_of_view] to calculate a field of view.
 pub trait ViewableField {
     /// Returns `true` if the tile at the given coordinates is opaque.
     fn is_opaque(&self, x: i32, y: i32) -> bool;
+
+    /// Returns how many tiles of sight range are used up by seeing through the tile at the given
+    /// coordinates, e.g. for smoke or foliage.  The tile itself can still be seen.  Defaults to 0.
+    fn sight_cost(&self, _x: i32, _y: i32) -> i32 {
+        0
+    }
+}
+
+/// ((low_dy, low_dx), (high_dy, high_dx), cost), where cost is the sight range used up so far.
+type Sight = ((i32, i32), (i32, i32), i32);
+
+/// (real_x_from_x, real_x_from_y, real_y_from_x, real_y_from_y, include_edges) for each octant.
+const OCTANT_DATA: [(i32, i32, i32, i32, bool); 8] = [
+    (1, 0, 0, 1, true),
+    (0, 1, 1, 0, false),
+    (0, -1, 1, 0, true),
+    (-1, 0, 0, 1, false),
+    (-1, 0, 0, -1, true),
+    (0, -1, -1, 0, false),
+    (0, 1, -1, 0, true),
+    (1, 0, 0, -1, false),
+];
+
+/// Length that the edges of a [FovShape::Cone] are scaled to before being rounded to integers.
+const CONE_EDGE_SCALE: f32 = 1024.0;
+
+/// The edges of a [FovShape::Cone] as integer vectors, clockwise and counter-clockwise of its
+/// direction respectively, and whether the cone is wider than a half circle.
+type ConeEdges = ((i32, i32), (i32, i32), bool);
+
+/// Returns `true` if the direction `v` lies within the cone described by `edges`, edges included.
+fn in_cone(edges: ConeEdges, v: (i32, i32)) -> bool {
+    let ((right_x, right_y), (left_x, left_y), wide) = edges;
+    let (right_x, right_y) = (right_x as i64, right_y as i64);
+    let (left_x, left_y) = (left_x as i64, left_y as i64);
+    let (vx, vy) = (v.0 as i64, v.1 as i64);
+    let right_of_v = right_x * vy - right_y * vx >= 0;
+    let v_right_of_left = vx * left_y - vy * left_x >= 0;
+
+    if wide {
+        right_of_v || v_right_of_left
+    } else {
+        // The dot product with the bisector rules out the mirror image of narrow cones.
+        right_of_v
+            && v_right_of_left
+            && (vx * (right_x + left_x) + vy * (right_y + left_y) > 0
+                || (right_x, right_y) == (-left_x, -left_y))
+    }
+}
+
+/// Returns the span of slopes of the given octant that covers the parts of it within the cone
+/// described by `edges`, if any.  This includes the gap of a cone wide enough to have its gap inside
+/// the octant, so tiles still need to be checked with [in_cone].
+fn cone_sight(edges: ConeEdges, octant: usize) -> Option<Sight> {
+    let (a, b, c, d, _) = OCTANT_DATA[octant];
+    let to_real = |(n, dx): (i32, i32)| (a * dx + b * n, c * dx + d * n);
+    let cmp = |&(a_n, a_d): &(i32, i32), &(b_n, b_d): &(i32, i32)| (a_n * b_d).cmp(&(b_n * a_d));
+
+    // Slopes where the cone may start or stop within the octant.
+    let mut slopes = vec![(0, 1), (1, 1)];
+    for &(ex, ey) in &[edges.0, edges.1] {
+        // Transform the edge into octant space; the octant transform is its own transpose.
+        let (local_x, local_y) = (a * ex + c * ey, b * ex + d * ey);
+
+        if local_x > 0 && local_y >= 0 && local_y <= local_x {
+            slopes.push((local_y, local_x));
+        }
+    }
+    slopes.sort_by(cmp);
+    slopes.dedup_by(|s2, s1| cmp(s1, s2).is_eq());
+
+    // Pieces of the octant in the cone in ascending order, including lone edge slopes.
+    let mut pieces = Vec::new();
+    for (i, &slope) in slopes.iter().enumerate() {
+        if in_cone(edges, to_real(slope)) {
+            pieces.push((slope, slope));
+        }
+        if let Some(&next) = slopes.get(i + 1) {
+            let mid = (slope.0 * next.1 + next.0 * slope.1, 2 * slope.1 * next.1);
+
+            if in_cone(edges, to_real(mid)) {
+                pieces.push((slope, next));
+            }
+        }
+    }
+
+    Some((pieces.first()?.0, pieces.last()?.1, 0))
 }
 
 /// Iterator returned by [field_of_view] that iterates over each tile in the field of view.
@@ -29,11 +119,13 @@ pub struct FovIter<'a, T: BoundedMap + ViewableField> {
 
     bounds: (i32, i32, i32, i32), // min_x, min_y, max_x, max_y
     max_dist2: i32,
-    sights_even: Vec<((i32, i32), (i32, i32))>, // ((low_dy, low_dx), (high_dy, high_dx))
-    sights_odd: Vec<((i32, i32), (i32, i32))>,  // ^
+    cone: Option<ConeEdges>,
+    sights_even: Vec<Sight>,
+    sights_odd: Vec<Sight>,
     low_y: i32,
     high_y: i32,
     low_sight_angle: Option<(i32, i32)>,
+    low_sight_cost: i32,
 
     octant: Option<i32>,
     x: Option<i32>,
@@ -74,10 +166,21 @@ impl<T: BoundedMap + ViewableField> FovIter<'_, T> {
             self.octant = Some(octant + 1);
         } else if octant < 8 {
             if self.x.is_none() {
-                // Kick off with sight of the full octant.
                 self.sights_odd.clear();
-                self.sights_odd.push(((0, 1), (1, 1)));
-                self.x = Some(1);
+                if let Some(edges) = self.cone {
+                    // Kick off with sight of only the part of the octant within the cone.
+                    self.sights_odd.extend(cone_sight(edges, octant as usize));
+                } else {
+                    // Kick off with sight of the full octant.
+                    self.sights_odd.push(((0, 1), (1, 1), 0));
+                }
+
+                // Skip octants that lie entirely outside of a cone.
+                self.x = Some(if self.sights_odd.is_empty() {
+                    self.range + 1
+                } else {
+                    1
+                });
             }
 
             let x = self.x.unwrap();
@@ -100,7 +203,7 @@ impl<T: BoundedMap + ViewableField> FovIter<'_, T> {
                 let s = self.s.unwrap();
 
                 if s < current.len() {
-                    let (low_angle, high_angle) = current[s];
+                    let (low_angle, high_angle, cost) = current[s];
 
                     if self.y.is_none() {
                         // Calculate the low and high tiles whose middle lines are cut by the angles.
@@ -115,29 +218,22 @@ impl<T: BoundedMap + ViewableField> FovIter<'_, T> {
 
                     let y = self.y.unwrap();
 
+                    // Sight range used up by partially transparent tiles counts as extra distance.
                     let in_shape = match self.fov_shape {
-                        FovShape::Square => true,
-                        FovShape::Circle | FovShape::CirclePlus => x * x + y * y <= self.max_dist2,
+                        FovShape::Square => x + cost <= self.range,
+                        FovShape::Circle | FovShape::CirclePlus | FovShape::Cone { .. } => {
+                            (x + cost) * (x + cost) + y * y <= self.max_dist2
+                        }
                     };
 
                     if in_shape && y <= self.high_y {
-                        let octant_data = [
-                            (1, 0, 0, 1, true),
-                            (0, 1, 1, 0, false),
-                            (0, -1, 1, 0, true),
-                            (-1, 0, 0, 1, false),
-                            (-1, 0, 0, -1, true),
-                            (0, -1, -1, 0, false),
-                            (0, 1, -1, 0, true),
-                            (1, 0, 0, -1, false),
-                        ];
                         let (
                             real_x_from_x,
                             real_x_from_y,
                             real_y_from_x,
                             real_y_from_y,
                             include_edges,
-                        ) = octant_data[octant as usize];
+                        ) = OCTANT_DATA[octant as usize];
 
                         let real_x = self.start_pos.0 + x * real_x_from_x + y * real_x_from_y;
                         let real_y = self.start_pos.1 + x * real_y_from_x + y * real_y_from_y;
@@ -151,21 +247,59 @@ impl<T: BoundedMap + ViewableField> FovIter<'_, T> {
                         if in_bounds(real_x, real_y) && self.map.is_opaque(real_x, real_y) {
                             // Finish the current sight when hitting an opaque tile.
                             if self.low_sight_angle.is_some() {
-                                next.push((self.low_sight_angle.unwrap(), low_mid_angle));
+                                next.push((
+                                    self.low_sight_angle.unwrap(),
+                                    low_mid_angle,
+                                    self.low_sight_cost,
+                                ));
+                                self.low_sight_angle = None;
+                            }
+                        } else {
+                            let next_cost = cost
+                                + if in_bounds(real_x, real_y) {
+                                    self.map.sight_cost(real_x, real_y).max(0)
+                                } else {
+                                    0
+                                };
+
+                            // Finish the current sight when the cost of seeing past tiles changes.
+                            if self.low_sight_angle.is_some() && next_cost != self.low_sight_cost {
+                                next.push((
+                                    self.low_sight_angle.unwrap(),
+                                    low_mid_angle,
+                                    self.low_sight_cost,
+                                ));
                                 self.low_sight_angle = None;
                             }
-                        } else if self.low_sight_angle.is_none() {
-                            // Begin a new sight with the higher of the bottom center of the
-                            // current tile and the low angle.
-                            self.low_sight_angle = if angle_lt_or_eq(low_angle, low_mid_angle) {
-                                Some(low_mid_angle)
-                            } else {
-                                Some(low_angle)
-                            };
+
+                            if self.low_sight_angle.is_none() {
+                                // Begin a new sight with the higher of the bottom center of the
+                                // current tile and the low angle.
+                                self.low_sight_angle = if angle_lt_or_eq(low_angle, low_mid_angle) {
+                                    Some(low_mid_angle)
+                                } else {
+                                    Some(low_angle)
+                                };
+                                self.low_sight_cost = next_cost;
+                            }
                         }
 
+                        // Sight may clip tiles just outside the edges of a cone.
+                        let in_cone = self
+                            .cone
+                            .map(|edges| {
+                                in_cone(
+                                    edges,
+                                    (real_x - self.start_pos.0, real_y - self.start_pos.1),
+                                )
+                            })
+                            .unwrap_or(true);
+
                         // Visit the tile.
-                        if (include_edges || (y > 0 && y < x)) && in_bounds(real_x, real_y) {
+                        if (include_edges || (y > 0 && y < x))
+                            && in_bounds(real_x, real_y)
+                            && in_cone
+                        {
                             out_pos = Some((real_x, real_y));
                             out_symmetric = angle_lt_or_eq(low_angle, (y, x))
                                 && angle_lt_or_eq((y, x), high_angle);
@@ -175,7 +309,7 @@ impl<T: BoundedMap + ViewableField> FovIter<'_, T> {
                     } else {
                         // Finish any sight left dangling.
                         if let Some(low_sight_angle) = self.low_sight_angle {
-                            next.push((low_sight_angle, high_angle));
+                            next.push((low_sight_angle, high_angle, self.low_sight_cost));
                         }
 
                         self.s = Some(s + 1);
@@ -231,6 +365,9 @@ impl<T: BoundedMap + ViewableField> Iterator for FovIter<'_, T> {
 /// `start_pos` are the (x, y) coordinates to calculate field of view from.
 ///
 /// `range` must be non-negative.
+///
+/// A [FovShape::Cone] only casts sight through the parts of each octant within the cone, so tiles
+/// outside of it are never visited.
 pub fn field_of_view<T>(
     map: &'_ T,
     start_pos: (i32, i32),
@@ -245,7 +382,29 @@ where
     let max_dist2 = match fov_shape {
         FovShape::Square => 0, // unused
         FovShape::Circle => range * range,
-        FovShape::CirclePlus => range * (range + 1),
+        FovShape::CirclePlus | FovShape::Cone { .. } => range * (range + 1),
+    };
+    let cone = match fov_shape {
+        FovShape::Cone { dir, half_angle } if half_angle < 180 && dir != (0, 0) => {
+            let half_angle = half_angle.max(0);
+            let len = ((dir.0 * dir.0 + dir.1 * dir.1) as f32).sqrt();
+            let (dir_x, dir_y) = (dir.0 as f32 / len, dir.1 as f32 / len);
+            let edge = |angle: f32| {
+                let (sin, cos) = angle.to_radians().sin_cos();
+
+                (
+                    ((dir_x * cos - dir_y * sin) * CONE_EDGE_SCALE).round() as i32,
+                    ((dir_x * sin + dir_y * cos) * CONE_EDGE_SCALE).round() as i32,
+                )
+            };
+
+            Some((
+                edge(-half_angle as f32),
+                edge(half_angle as f32),
+                half_angle > 90,
+            ))
+        }
+        _ => None,
     };
 
     FovIter {
@@ -256,11 +415,13 @@ where
 
         bounds: map.bounds(),
         max_dist2,
+        cone,
         sights_even: Vec::with_capacity(range as usize),
         sights_odd: Vec::with_capacity(range as usize),
         low_y: 0,
         high_y: 0,
         low_sight_angle: None,
+        low_sight_cost: 0,
 
         octant: None,
         x: None,
@@ -268,3 +429,304 @@ where
         y: None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_xoshiro::Xoshiro128PlusPlus as GameRng;
+    use std::collections::HashMap;
+
+    /// A map drawn as rows of characters, where `#` is opaque and digits cost that much sight range
+    /// to see through.
+    struct TestMap(Vec<Vec<char>>);
+
+    impl TestMap {
+        fn new(rows: &[&str]) -> Self {
+            Self(rows.iter().map(|row| row.chars().collect()).collect())
+        }
+
+        fn random(rng: &mut GameRng, width: usize, height: usize) -> Self {
+            Self(
+                (0..height)
+                    .map(|_| {
+                        (0..width)
+                            .map(|_| if rng.gen_ratio(1, 6) { '#' } else { '.' })
+                            .collect()
+                    })
+                    .collect(),
+            )
+        }
+    }
+
+    impl BoundedMap for TestMap {
+        fn bounds(&self) -> (i32, i32, i32, i32) {
+            (0, 0, self.0[0].len() as i32 - 1, self.0.len() as i32 - 1)
+        }
+    }
+
+    impl ViewableField for TestMap {
+        fn is_opaque(&self, x: i32, y: i32) -> bool {
+            self.0[y as usize][x as usize] == '#'
+        }
+
+        fn sight_cost(&self, x: i32, y: i32) -> i32 {
+            self.0[y as usize][x as usize].to_digit(10).unwrap_or(0) as i32
+        }
+    }
+
+    /// Map each visited tile to whether it was symmetric, checking that no tile is visited twice.
+    fn visit(
+        map: &TestMap,
+        start: (i32, i32),
+        range: i32,
+        shape: FovShape,
+    ) -> HashMap<(i32, i32), bool> {
+        let mut tiles = HashMap::new();
+
+        for (x, y, symmetric) in field_of_view(map, start, range, shape) {
+            assert!(
+                tiles.insert((x, y), symmetric).is_none(),
+                "{:?} visited twice",
+                (x, y)
+            );
+        }
+
+        tiles
+    }
+
+    /// Angle in degrees between the direction of a cone and the given offset.
+    fn angle_from((dir_x, dir_y): (i32, i32), (dx, dy): (i32, i32)) -> f32 {
+        let dot = (dir_x * dx + dir_y * dy) as f32;
+        let lens = (((dir_x * dir_x + dir_y * dir_y) * (dx * dx + dy * dy)) as f32).sqrt();
+
+        (dot / lens).clamp(-1.0, 1.0).acos().to_degrees()
+    }
+
+    #[test]
+    fn cones_are_within_circles() {
+        let mut rng = GameRng::seed_from_u64(15);
+        let start = (10, 10);
+
+        for _ in 0..300 {
+            let map = TestMap::random(&mut rng, 21, 21);
+            let range = rng.gen_range(0..10);
+            let dir = loop {
+                let dir = (rng.gen_range(-3..=3), rng.gen_range(-3..=3));
+                if dir != (0, 0) {
+                    break dir;
+                }
+            };
+            let half_angle = rng.gen_range(0..200);
+
+            let circle = visit(&map, start, range, FovShape::CirclePlus);
+            let cone = visit(&map, start, range, FovShape::Cone { dir, half_angle });
+
+            for (&pos, &symmetric) in &cone {
+                let offset = (pos.0 - start.0, pos.1 - start.1);
+
+                assert!(
+                    circle.contains_key(&pos),
+                    "{:?} {:?} {}",
+                    pos,
+                    dir,
+                    half_angle
+                );
+                if symmetric {
+                    assert!(circle[&pos], "{:?} {:?} {}", pos, dir, half_angle);
+                    if offset != (0, 0) {
+                        assert!(angle_from(dir, offset) < half_angle as f32 + 0.5);
+                    }
+                }
+            }
+
+            for (&pos, &symmetric) in &circle {
+                let offset = (pos.0 - start.0, pos.1 - start.1);
+
+                if symmetric
+                    && (offset == (0, 0) || angle_from(dir, offset) < half_angle as f32 - 0.5)
+                {
+                    assert_eq!(
+                        cone.get(&pos),
+                        Some(&true),
+                        "{:?} {:?} {}",
+                        pos,
+                        dir,
+                        half_angle
+                    );
+                }
+            }
+
+            if half_angle >= 180 {
+                assert_eq!(cone, circle);
+            }
+        }
+    }
+
+    #[test]
+    fn cones_include_their_edges() {
+        let map = TestMap::new(&[".".repeat(15).as_str(); 15]);
+        let start = (7, 7);
+        let range = 7;
+
+        let right = visit(
+            &map,
+            start,
+            range,
+            FovShape::Cone {
+                dir: (1, 0),
+                half_angle: 45,
+            },
+        );
+        for d in 1..=5 {
+            assert_eq!(right.get(&(7 + d, 7 + d)), Some(&true));
+            assert_eq!(right.get(&(7 + d, 7 - d)), Some(&true));
+            assert_eq!(right.get(&(7 + d - 1, 7 + d)), None);
+            assert_eq!(right.get(&(7 + d - 1, 7 - d)), None);
+        }
+
+        let down_left = visit(
+            &map,
+            start,
+            range,
+            FovShape::Cone {
+                dir: (-1, 1),
+                half_angle: 45,
+            },
+        );
+        for d in 1..=range {
+            assert_eq!(down_left.get(&(7 - d, 7)), Some(&true));
+            assert_eq!(down_left.get(&(7, 7 + d)), Some(&true));
+            assert_eq!(down_left.get(&(7 + 1, 7 + d)), None);
+        }
+
+        let ray = visit(
+            &map,
+            start,
+            range,
+            FovShape::Cone {
+                dir: (0, -1),
+                half_angle: 0,
+            },
+        );
+        let mut expected: HashMap<_, _> = (0..=range).map(|d| ((7, 7 - d), true)).collect();
+        assert_eq!(ray, expected);
+
+        // Everything but a ray straight back.
+        let wide = visit(
+            &map,
+            start,
+            range,
+            FovShape::Cone {
+                dir: (0, -1),
+                half_angle: 179,
+            },
+        );
+        expected = visit(&map, start, range, FovShape::CirclePlus);
+        for d in 1..=range {
+            expected.remove(&(7, 7 + d));
+        }
+        assert_eq!(wide, expected);
+    }
+
+    #[test]
+    fn mirrored_cones_see_mirrored_tiles() {
+        let mut rng = GameRng::seed_from_u64(1515);
+        let start = (10, 10);
+
+        for _ in 0..200 {
+            let map = TestMap::random(&mut rng, 21, 21);
+            let mirror = TestMap(map.0.iter().rev().cloned().collect());
+            let range = rng.gen_range(0..10);
+            let dir = (rng.gen_range(-3..=3), rng.gen_range(-3..=3));
+            let half_angle = rng.gen_range(0..180);
+
+            let cone = visit(&map, start, range, FovShape::Cone { dir, half_angle });
+            let mirrored_cone: HashMap<_, _> = visit(
+                &mirror,
+                start,
+                range,
+                FovShape::Cone {
+                    dir: (dir.0, -dir.1),
+                    half_angle,
+                },
+            )
+            .into_iter()
+            .map(|((x, y), symmetric)| ((x, 20 - y), symmetric))
+            .collect();
+
+            assert_eq!(cone, mirrored_cone, "{:?} {}", dir, half_angle);
+        }
+    }
+
+    /// Columns of the tiles on the starting row that are in view, in order.
+    fn row_in_view(row: &str, start_x: i32, range: i32, shape: FovShape) -> Vec<i32> {
+        let map = TestMap::new(&[row]);
+        let mut xs: Vec<_> = visit(&map, (start_x, 0), range, shape)
+            .into_keys()
+            .map(|(x, _)| x)
+            .collect();
+
+        xs.sort_unstable();
+        xs
+    }
+
+    #[test]
+    fn sight_costs_shorten_range_beyond_the_tile() {
+        assert_eq!(
+            row_in_view("............", 0, 6, FovShape::Square),
+            (0..=6).collect::<Vec<_>>()
+        );
+        // The costly tile itself is seen, but everything past it is 2 tiles further away.
+        assert_eq!(
+            row_in_view("..2.........", 0, 6, FovShape::Square),
+            (0..=4).collect::<Vec<_>>()
+        );
+        // Costs add up.
+        assert_eq!(
+            row_in_view("..1.1.......", 0, 6, FovShape::Square),
+            (0..=4).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            row_in_view("..1.1.......", 0, 6, FovShape::Circle),
+            (0..=4).collect::<Vec<_>>()
+        );
+        // Costs too high see no further than the costly tile.
+        assert_eq!(
+            row_in_view("..9.........", 0, 6, FovShape::CirclePlus),
+            (0..=2).collect::<Vec<_>>()
+        );
+        // Standing in a costly tile costs nothing.
+        assert_eq!(
+            row_in_view("3...........", 0, 6, FovShape::Square),
+            (0..=6).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn sight_costs_only_affect_sight_through_the_tile() {
+        let map = TestMap::new(&[
+            ".........", //
+            ".........",
+            "....3....",
+            ".........",
+            ".........",
+        ]);
+        let clear = TestMap::new(&["........."; 5]);
+        let start = (4, 0);
+        let seen = visit(&map, start, 4, FovShape::CirclePlus);
+        let clear_seen = visit(&clear, start, 4, FovShape::CirclePlus);
+
+        // Straight past the costly tile is out of range...
+        assert!(seen.contains_key(&(4, 2)));
+        assert!(!seen.contains_key(&(4, 3)));
+        assert!(!seen.contains_key(&(4, 4)));
+        // ...but tiles to either side are still seen.
+        for pos in [(1, 3), (2, 3), (6, 3), (7, 3), (2, 4), (6, 4)] {
+            assert_eq!(seen.get(&pos), Some(&true), "{:?}", pos);
+            assert_eq!(clear_seen.get(&pos), Some(&true), "{:?}", pos);
+        }
+        assert!(seen.len() < clear_seen.len());
+        assert!(seen.keys().all(|pos| clear_seen.contains_key(pos)));
+    }
+}
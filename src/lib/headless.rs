@@ -0,0 +1,175 @@
+use crate::{
+    input_buffer::{InputBuffer, InputEvent},
+    run::{RunControl, RunSettings},
+    tilegrid::{Symbol, TileGridLayer, Tileset},
+    util::Size,
+};
+
+/// Run a main loop like [crate::run()], but without creating a window or needing a display.
+///
+/// Instead of polling for window events, input events are pulled from `events` one at a time:
+/// one event is fed in before each update while `update` requests [RunControl::Update], and
+/// [RunControl::WaitForEvent] consumes the next event immediately instead of blocking.  The loop
+/// ends when `update` returns [RunControl::Quit], or when it wants to wait for an event after
+/// `events` has run dry.
+///
+/// Updates don't wait for wall clock time, so `settings.fps` is ignored, and the window size given
+/// to `update` is always `settings.window_size`, so `settings.window_mode` and
+/// `settings.integer_scale` are ignored too.
+///
+/// After each update, `present` receives the visible layers in the place where [crate::run()] would
+/// display them, starting from the lowest visible layer; their contents can be read back with
+/// [crate::TileGrid::get_cell_text].  Tilesets are still loaded as software surfaces, so the tile
+/// images must be available.
+pub fn run_headless<I, U, P, Y>(settings: RunSettings<Y>, events: I, mut update: U, mut present: P)
+where
+    I: IntoIterator<Item = InputEvent>,
+    U: FnMut(&mut InputBuffer, &mut Vec<TileGridLayer<Y>>, &[Tileset<Y>], Size) -> RunControl,
+    P: FnMut(&[TileGridLayer<Y>]),
+    Y: Symbol,
+{
+    let _image_context = sdl2::image::init(sdl2::image::InitFlag::PNG).unwrap();
+
+    assert!(!settings.tileset_infos.is_empty());
+
+    let mut tilesets = Vec::with_capacity(settings.tileset_infos.len());
+    for tileset_info in settings.tileset_infos {
+        tilesets.push(Tileset::new(tileset_info));
+    }
+
+    let window_size = settings.window_size;
+    let mut events = events.into_iter();
+    let mut layers: Vec<TileGridLayer<Y>> = Vec::new();
+    let mut inputs = InputBuffer::new();
+    let mut active_update = true;
+
+    loop {
+        if active_update {
+            // Trickle in scripted events one per frame while updating actively.
+            if let Some(event) = events.next() {
+                inputs.push_event(event);
+            }
+        } else if !inputs.more_inputs() {
+            // Waiting on an empty script would block forever, so stop here instead.
+            match events.next() {
+                Some(event) => inputs.push_event(event),
+                None => break,
+            }
+        }
+
+        match update(&mut inputs, &mut layers, &tilesets[..], window_size) {
+            RunControl::Update => active_update = true,
+            RunControl::WaitForEvent => active_update = false,
+            RunControl::Quit => break,
+        }
+
+        // There's no canvas to read pixels back from or window to change, so drop any such requests.
+        inputs.take_screenshot_request();
+        inputs.take_window_mode_request();
+        inputs.take_integer_scale_request();
+
+        // Present the grids, starting from the lowest visible layer.
+        let start_layer_draw_from = layers.iter().rposition(|l| !l.draw_behind).unwrap_or(0);
+
+        present(&layers[start_layer_draw_from..]);
+
+        for layer in &mut layers {
+            for grid in &mut layer.grids {
+                grid.advance_animations();
+            }
+        }
+
+        // Discard any current input to make way for the next one.
+        inputs.clear_input();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sdl2::keyboard::Keycode;
+
+    use super::*;
+    use crate::{
+        run::WindowMode,
+        tilegrid::{
+            layers_to_text,
+            tests::{font_tileset_info, NoSym},
+            TileGrid,
+        },
+    };
+
+    /// Run scripted events through an update that shows the last key pressed, returning the text
+    /// of each presented frame.
+    fn run_script(events: Vec<InputEvent>) -> Vec<String> {
+        let settings = RunSettings::<NoSym> {
+            title: "test".into(),
+            window_size: (80, 28).into(),
+            min_window_size: (80, 28).into(),
+            window_mode: WindowMode::Windowed,
+            integer_scale: false,
+            fps: 30,
+            tileset_infos: vec![font_tileset_info()],
+            hot_reload_tilesets: false,
+        };
+        let mut frames = Vec::new();
+
+        run_headless(
+            settings,
+            events,
+            |inputs, layers, tilesets, _| {
+                if layers.is_empty() {
+                    layers.push(TileGridLayer {
+                        draw_behind: false,
+                        grids: vec![TileGrid::new((6, 1).into(), tilesets, 0)],
+                    });
+                }
+
+                inputs.prepare_input();
+
+                match inputs.get_input() {
+                    Some(InputEvent::AppQuit) => return RunControl::Quit,
+                    Some(InputEvent::Press(key)) => {
+                        let grid = &mut layers[0].grids[0];
+                        grid.clear();
+                        grid.print((0, 0), &format!("{:?}", key));
+                    }
+                    _ => {}
+                }
+
+                RunControl::WaitForEvent
+            },
+            |layers| frames.push(layers_to_text(layers)),
+        );
+
+        frames
+    }
+
+    #[test]
+    fn presents_a_frame_per_event() {
+        let frames = run_script(vec![
+            InputEvent::Press(Keycode::A),
+            InputEvent::Press(Keycode::Space),
+            InputEvent::Release(Keycode::Space),
+        ]);
+
+        assert_eq!(
+            frames,
+            vec![
+                "--- layer 0 grid 0 (6x1) ---\nA     \n",
+                "--- layer 0 grid 0 (6x1) ---\nSpace \n",
+                "--- layer 0 grid 0 (6x1) ---\nSpace \n",
+            ],
+        );
+    }
+
+    #[test]
+    fn quit_stops_before_presenting() {
+        let frames = run_script(vec![
+            InputEvent::Press(Keycode::A),
+            InputEvent::AppQuit,
+            InputEvent::Press(Keycode::B),
+        ]);
+
+        assert_eq!(frames, vec!["--- layer 0 grid 0 (6x1) ---\nA     \n"]);
+    }
+}
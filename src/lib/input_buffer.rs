@@ -1,14 +1,47 @@
-use sdl2::{event::Event, keyboard::Keycode};
-use std::collections::VecDeque;
+use sdl2::{
+    controller::{Axis, Button},
+    event::Event,
+    keyboard::Keycode,
+    mouse::{MouseButton, MouseWheelDirection},
+};
+use std::{collections::VecDeque, path::PathBuf};
+
+use crate::{run::WindowMode, util::Position};
 
 /// Input events buffered by and emitted from an [InputBuffer].
-#[derive(Clone, Copy)]
+#[derive(Clone)]
 pub enum InputEvent {
     AppQuit,
     Press(Keycode),
     Release(Keycode),
+    /// Mouse cursor moved to a pixel position in the window, measured in the pixels that TileGrids
+    /// are laid out in; see [InputBuffer::set_mouse_transform].
+    MouseMove(Position),
+    /// Mouse button pressed at a pixel position in the window.
+    MousePress(MouseButton, Position),
+    /// Mouse button released at a pixel position in the window.
+    MouseRelease(MouseButton, Position),
+    /// Mouse wheel scrolled by `(x, y)` steps, with positive y away from the user, while the cursor
+    /// was at a pixel position in the window.
+    MouseWheel((i32, i32), Position),
+    /// Text typed while text input is active; see [InputBuffer::start_text_input].
+    Text(String),
+    /// Game controller button pressed.
+    ControllerPress(Button),
+    /// Game controller button released.
+    ControllerRelease(Button),
+    /// Game controller axis moved into a different zone, i.e. pushed past [AXIS_DEAD_ZONE] in
+    /// either direction or returned to within it, along with the raw axis value.
+    ControllerAxis(Axis, i16),
 }
 
+/// Axis values closer to zero than this are treated as centered when deciding whether to buffer an
+/// [InputEvent::ControllerAxis] event.
+pub const AXIS_DEAD_ZONE: i16 = 16384;
+
+/// Number of game controller axes tracked by an [InputBuffer].
+const NUM_AXES: usize = 6;
+
 bitflags! {
     /// Modifier key flags, tracked by an [InputBuffer] and checked via [InputBuffer::get_mods].
     pub struct KeyMods: u8 {
@@ -55,6 +88,14 @@ pub struct InputBuffer {
     buffer: VecDeque<InputEvent>,
     current_input: Option<InputEvent>,
     keymods: KeyMods,
+    mouse_pos: Position,
+    mouse_offset: Position,
+    mouse_scale: u32,
+    text_input: bool,
+    screenshot_path: Option<PathBuf>,
+    window_mode_request: Option<WindowMode>,
+    integer_scale_request: Option<bool>,
+    axis_zones: [i8; NUM_AXES],
 }
 
 impl Default for InputBuffer {
@@ -70,6 +111,33 @@ impl InputBuffer {
             buffer: VecDeque::new(),
             current_input: None,
             keymods: KeyMods::empty(),
+            mouse_pos: Position { x: 0, y: 0 },
+            mouse_offset: Position { x: 0, y: 0 },
+            mouse_scale: 1,
+            text_input: false,
+            screenshot_path: None,
+            window_mode_request: None,
+            integer_scale_request: None,
+            axis_zones: [0; NUM_AXES],
+        }
+    }
+
+    /// Set where the area that TileGrids are displayed in starts in the window and the whole number
+    /// scale it's displayed at, so that mouse positions can be measured in the pixels that TileGrids
+    /// are laid out in.  Main loops call this whenever the display changes.
+    pub fn set_mouse_transform(&mut self, offset: Position, scale: u32) {
+        assert!(scale > 0);
+
+        self.mouse_offset = offset;
+        self.mouse_scale = scale;
+    }
+
+    fn window_to_view_pos(&self, x: i32, y: i32) -> Position {
+        let scale = self.mouse_scale as i32;
+
+        Position {
+            x: (x - self.mouse_offset.x).div_euclid(scale),
+            y: (y - self.mouse_offset.y).div_euclid(scale),
         }
     }
 
@@ -82,11 +150,78 @@ impl InputBuffer {
             Event::KeyUp {
                 keycode: Some(key), ..
             } => self.buffer.push_back(InputEvent::Release(*key)),
+            Event::MouseMotion { x, y, .. } => {
+                self.mouse_pos = self.window_to_view_pos(*x, *y);
+
+                // Collapse runs of mouse motion so they don't hold up other inputs.
+                if let Some(InputEvent::MouseMove(pos)) = self.buffer.back_mut() {
+                    *pos = self.mouse_pos;
+                } else {
+                    self.buffer.push_back(InputEvent::MouseMove(self.mouse_pos));
+                }
+            }
+            Event::MouseButtonDown {
+                mouse_btn, x, y, ..
+            } => {
+                self.mouse_pos = self.window_to_view_pos(*x, *y);
+                self.buffer
+                    .push_back(InputEvent::MousePress(*mouse_btn, self.mouse_pos));
+            }
+            Event::MouseButtonUp {
+                mouse_btn, x, y, ..
+            } => {
+                self.mouse_pos = self.window_to_view_pos(*x, *y);
+                self.buffer
+                    .push_back(InputEvent::MouseRelease(*mouse_btn, self.mouse_pos));
+            }
+            Event::MouseWheel {
+                x, y, direction, ..
+            } => {
+                let (x, y) = if *direction == MouseWheelDirection::Flipped {
+                    (-*x, -*y)
+                } else {
+                    (*x, *y)
+                };
+
+                self.buffer
+                    .push_back(InputEvent::MouseWheel((x, y), self.mouse_pos));
+            }
+            Event::TextInput { text, .. } if self.text_input => {
+                self.buffer.push_back(InputEvent::Text(text.clone()));
+            }
+            Event::ControllerButtonDown { button, .. } => {
+                self.buffer.push_back(InputEvent::ControllerPress(*button))
+            }
+            Event::ControllerButtonUp { button, .. } => self
+                .buffer
+                .push_back(InputEvent::ControllerRelease(*button)),
+            Event::ControllerAxisMotion { axis, value, .. } => {
+                // Only buffer axis motion that crosses into a different zone to avoid flooding.
+                let zone = if *value <= -AXIS_DEAD_ZONE {
+                    -1
+                } else if *value >= AXIS_DEAD_ZONE {
+                    1
+                } else {
+                    0
+                };
+                let axis_zone = &mut self.axis_zones[*axis as usize];
+
+                if *axis_zone != zone {
+                    *axis_zone = zone;
+                    self.buffer
+                        .push_back(InputEvent::ControllerAxis(*axis, *value));
+                }
+            }
             Event::Quit { .. } => self.buffer.push_back(InputEvent::AppQuit),
             _ => {}
         }
     }
 
+    /// Buffer an input event directly, e.g. to feed in scripted input without an SDL event pump.
+    pub fn push_event(&mut self, input: InputEvent) {
+        self.buffer.push_back(input);
+    }
+
     /// If no event is prepared, set current input event to the next one in the buffer.
     /// If an event is already prepared, do nothing.
     pub fn prepare_input(&mut self) {
@@ -94,7 +229,7 @@ impl InputBuffer {
             self.current_input = self.buffer.pop_front();
 
             // Track modifier keys.
-            if let Some(input) = self.current_input {
+            if let Some(input) = &self.current_input {
                 match input {
                     InputEvent::Press(keycode) => match keycode {
                         Keycode::LShift => self.keymods |= KeyMods::LSHIFT,
@@ -122,7 +257,7 @@ impl InputBuffer {
 
     /// Get the current input event.
     pub fn get_input(&self) -> Option<InputEvent> {
-        self.current_input
+        self.current_input.clone()
     }
 
     /// Get modifier keys that were active when the current input event was received.
@@ -130,6 +265,62 @@ impl InputBuffer {
         !(self.keymods & mods).is_empty()
     }
 
+    /// Get the last known pixel position of the mouse cursor in the window, measured in the pixels
+    /// that TileGrids are laid out in.
+    pub fn get_mouse_pos(&self) -> Position {
+        self.mouse_pos
+    }
+
+    /// Request text input events, e.g. for a text entry field.  The main loop turns on text input
+    /// in response, so [InputEvent::Text] events will be buffered until
+    /// [InputBuffer::stop_text_input] is called.
+    pub fn start_text_input(&mut self) {
+        self.text_input = true;
+    }
+
+    /// Stop requesting text input events.
+    pub fn stop_text_input(&mut self) {
+        self.text_input = false;
+    }
+
+    /// Returns true if text input events have been requested.
+    pub fn is_text_input_active(&self) -> bool {
+        self.text_input
+    }
+
+    /// Request that the main loop saves a PNG screenshot of the next displayed frame to `path`; see
+    /// [crate::save_screenshot].  Headless main loops have no window, so they drop the request.
+    pub fn request_screenshot(&mut self, path: PathBuf) {
+        self.screenshot_path = Some(path);
+    }
+
+    /// Take the path of a requested screenshot, if any, clearing the request.
+    pub fn take_screenshot_request(&mut self) -> Option<PathBuf> {
+        self.screenshot_path.take()
+    }
+
+    /// Request that the main loop switches the window to the given mode after this update.  Main
+    /// loops without a window drop the request.
+    pub fn request_window_mode(&mut self, window_mode: WindowMode) {
+        self.window_mode_request = Some(window_mode);
+    }
+
+    /// Take a requested window mode, if any, clearing the request.
+    pub fn take_window_mode_request(&mut self) -> Option<WindowMode> {
+        self.window_mode_request.take()
+    }
+
+    /// Request that the main loop turns integer scaling of TileGrids on or off after this update;
+    /// see [crate::RunSettings::integer_scale].  Main loops without a window drop the request.
+    pub fn request_integer_scale(&mut self, integer_scale: bool) {
+        self.integer_scale_request = Some(integer_scale);
+    }
+
+    /// Take a requested integer scaling setting, if any, clearing the request.
+    pub fn take_integer_scale_request(&mut self) -> Option<bool> {
+        self.integer_scale_request.take()
+    }
+
     /// Clear the current input event.
     pub fn clear_input(&mut self) {
         self.current_input = None;
@@ -146,3 +337,112 @@ impl InputBuffer {
         !self.buffer.is_empty()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn axis_motion(axis: Axis, value: i16) -> Event {
+        Event::ControllerAxisMotion {
+            timestamp: 0,
+            which: 0,
+            axis,
+            value,
+        }
+    }
+
+    /// Pull all buffered axis events out of an InputBuffer.
+    fn buffered_axes(inputs: &mut InputBuffer) -> Vec<(Axis, i16)> {
+        let mut axes = Vec::new();
+
+        loop {
+            inputs.prepare_input();
+            match inputs.get_input() {
+                Some(InputEvent::ControllerAxis(axis, value)) => axes.push((axis, value)),
+                Some(_) => panic!("unexpected non-axis input"),
+                None => break,
+            }
+            inputs.clear_input();
+        }
+
+        axes
+    }
+
+    #[test]
+    fn axis_motion_within_a_zone_is_buffered_once() {
+        let mut inputs = InputBuffer::new();
+
+        inputs.handle_event(&axis_motion(Axis::LeftX, 100));
+        inputs.handle_event(&axis_motion(Axis::LeftX, AXIS_DEAD_ZONE - 1));
+        assert_eq!(buffered_axes(&mut inputs), vec![]);
+
+        inputs.handle_event(&axis_motion(Axis::LeftX, AXIS_DEAD_ZONE));
+        inputs.handle_event(&axis_motion(Axis::LeftX, AXIS_DEAD_ZONE + 1000));
+        inputs.handle_event(&axis_motion(Axis::LeftX, i16::MAX));
+        assert_eq!(
+            buffered_axes(&mut inputs),
+            vec![(Axis::LeftX, AXIS_DEAD_ZONE)]
+        );
+    }
+
+    #[test]
+    fn axis_returning_to_center_is_buffered_as_release() {
+        let mut inputs = InputBuffer::new();
+
+        inputs.handle_event(&axis_motion(Axis::LeftY, -AXIS_DEAD_ZONE));
+        inputs.handle_event(&axis_motion(Axis::LeftY, -5));
+        inputs.handle_event(&axis_motion(Axis::LeftY, 0));
+        assert_eq!(
+            buffered_axes(&mut inputs),
+            vec![(Axis::LeftY, -AXIS_DEAD_ZONE), (Axis::LeftY, -5)]
+        );
+    }
+
+    #[test]
+    fn axis_flipping_sides_is_buffered_for_each_side() {
+        let mut inputs = InputBuffer::new();
+
+        inputs.handle_event(&axis_motion(Axis::RightX, i16::MIN));
+        inputs.handle_event(&axis_motion(Axis::RightX, i16::MAX));
+        inputs.handle_event(&axis_motion(Axis::LeftX, i16::MAX));
+        assert_eq!(
+            buffered_axes(&mut inputs),
+            vec![
+                (Axis::RightX, i16::MIN),
+                (Axis::RightX, i16::MAX),
+                (Axis::LeftX, i16::MAX),
+            ]
+        );
+    }
+
+    #[test]
+    fn mouse_positions_are_scaled_to_the_display() {
+        let motion = |x, y| Event::MouseMotion {
+            timestamp: 0,
+            window_id: 0,
+            which: 0,
+            mousestate: sdl2::mouse::MouseState::from_sdl_state(0),
+            x,
+            y,
+            xrel: 0,
+            yrel: 0,
+        };
+        let mut inputs = InputBuffer::new();
+
+        inputs.handle_event(&motion(7, 5));
+        assert!(inputs.get_mouse_pos() == Position { x: 7, y: 5 });
+
+        inputs.set_mouse_transform(Position { x: 1, y: 2 }, 3);
+        inputs.handle_event(&motion(7, 5));
+        assert!(inputs.get_mouse_pos() == Position { x: 2, y: 1 });
+        // Positions in the margin before the display area are negative.
+        inputs.handle_event(&motion(0, 1));
+        assert!(inputs.get_mouse_pos() == Position { x: -1, y: -1 });
+
+        inputs.prepare_input();
+        match inputs.get_input() {
+            Some(InputEvent::MouseMove(pos)) => assert!(pos == Position { x: -1, y: -1 }),
+            _ => panic!("expected mouse motion"),
+        }
+    }
+}
@@ -2,19 +2,32 @@
 extern crate bitflags;
 
 mod field_of_view;
+mod headless;
 mod input_buffer;
+mod line;
+mod markup;
 mod path_find;
 mod run;
+#[cfg(not(target_os = "emscripten"))]
+mod terminal;
 mod tilegrid;
 pub mod util;
 mod word_wrap;
 
 pub use field_of_view::{field_of_view, FovIter, FovShape, ViewableField};
-pub use input_buffer::{InputBuffer, InputEvent, KeyMods};
-pub use path_find::{find_path, AStarIter, PathableMap};
-pub use run::{run, RunControl, RunSettings};
-pub use tilegrid::{Symbol, TileGrid, TileGridLayer, TileGridView, Tileset, TilesetInfo};
-pub use word_wrap::word_wrap;
+pub use headless::run_headless;
+pub use input_buffer::{InputBuffer, InputEvent, KeyMods, AXIS_DEAD_ZONE};
+pub use line::{line, line_of_fire, LineIter, LineOfFireIter};
+pub use markup::{color_tag, colored, escape_markup, parse_markup, strip_markup, MarkupIter};
+pub use path_find::{find_path, AStarIter, DijkstraMap, Neighborhood, PathableMap};
+pub use run::{run, RunControl, RunSettings, WindowMode};
+#[cfg(not(target_os = "emscripten"))]
+pub use terminal::run_terminal;
+pub use tilegrid::{
+    layers_to_text, save_screenshot, AnimationCell, AnimationGlyph, Symbol, TileAnimation,
+    TileGrid, TileGridLayer, TileGridView, Tileset, TilesetImage, TilesetInfo,
+};
+pub use word_wrap::{text_width, word_wrap, word_wrap_markup, word_wrap_with};
 
 /// A trait for a map that has minimum and maximum coordinate bounds.
 pub trait BoundedMap {
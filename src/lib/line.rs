@@ -0,0 +1,214 @@
+use super::{BoundedMap, PathableMap, ViewableField};
+
+/// Iterator returned by [line] that steps through each tile of a line from its start to its end.
+pub struct LineIter {
+    pos: Option<(i32, i32)>,
+    end: (i32, i32),
+    dx: i32,
+    dy: i32,
+    step_x: i32,
+    step_y: i32,
+    error: i32,
+}
+
+impl Iterator for LineIter {
+    type Item = (i32, i32);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let pos = self.pos?;
+
+        self.pos = if pos == self.end {
+            None
+        } else {
+            let (mut x, mut y) = pos;
+            let error2 = self.error * 2;
+
+            if error2 >= self.dy {
+                self.error += self.dy;
+                x += self.step_x;
+            }
+            if error2 <= self.dx {
+                self.error += self.dx;
+                y += self.step_y;
+            }
+
+            Some((x, y))
+        };
+
+        Some(pos)
+    }
+}
+
+/// Trace a line from `start` to `end` using Bresenham's line algorithm.  Both ends are included.
+pub fn line(start: (i32, i32), end: (i32, i32)) -> LineIter {
+    let dx = (end.0 - start.0).abs();
+    let dy = -(end.1 - start.1).abs();
+
+    LineIter {
+        pos: Some(start),
+        end,
+        dx,
+        dy,
+        step_x: if start.0 < end.0 { 1 } else { -1 },
+        step_y: if start.1 < end.1 { 1 } else { -1 },
+        error: dx + dy,
+    }
+}
+
+/// Iterator returned by [line_of_fire] that steps through each tile that a projectile would pass
+/// through.
+pub struct LineOfFireIter<'a, T: BoundedMap + PathableMap + ViewableField> {
+    map: &'a T,
+    line: LineIter,
+    stopped: bool,
+}
+
+impl<T: BoundedMap + PathableMap + ViewableField> Iterator for LineOfFireIter<'_, T> {
+    type Item = (i32, i32);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.stopped {
+            return None;
+        }
+
+        let (x, y) = self.line.next()?;
+        let (min_x, min_y, max_x, max_y) = self.map.bounds();
+
+        if x < min_x || x > max_x || y < min_y || y > max_y {
+            self.stopped = true;
+            None
+        } else {
+            self.stopped = self.map.is_opaque(x, y) || self.map.is_blocked(x, y);
+            Some((x, y))
+        }
+    }
+}
+
+/// Trace the path of a projectile fired from `start` towards `end`.
+///
+/// The starting tile is skipped, so that the shooter doesn't block their own shot.  The path ends
+/// at `end` or at the first opaque or blocked tile in the way, which is included since that is
+/// what the projectile hits.  Check if the last tile is `end` to see if the shot is clear.
+pub fn line_of_fire<T>(map: &'_ T, start: (i32, i32), end: (i32, i32)) -> LineOfFireIter<'_, T>
+where
+    T: BoundedMap + PathableMap + ViewableField,
+{
+    let mut line = line(start, end);
+
+    line.next();
+
+    LineOfFireIter {
+        map,
+        line,
+        stopped: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A map drawn as rows of characters, where `#` is a wall and `m` is a floor tile blocked by a
+    /// monster.
+    struct TestMap(Vec<Vec<char>>);
+
+    impl TestMap {
+        fn new(rows: &[&str]) -> Self {
+            Self(rows.iter().map(|row| row.chars().collect()).collect())
+        }
+    }
+
+    impl BoundedMap for TestMap {
+        fn bounds(&self) -> (i32, i32, i32, i32) {
+            (0, 0, self.0[0].len() as i32 - 1, self.0.len() as i32 - 1)
+        }
+    }
+
+    impl PathableMap for TestMap {
+        fn is_blocked(&self, x: i32, y: i32) -> bool {
+            matches!(self.0[y as usize][x as usize], '#' | 'm')
+        }
+    }
+
+    impl ViewableField for TestMap {
+        fn is_opaque(&self, x: i32, y: i32) -> bool {
+            self.0[y as usize][x as usize] == '#'
+        }
+    }
+
+    #[test]
+    fn lines_join_their_ends_in_every_octant() {
+        let ends = [
+            (5, 2),
+            (2, 5),
+            (-2, 5),
+            (-5, 2),
+            (-5, -2),
+            (-2, -5),
+            (2, -5),
+            (5, -2),
+            (4, 4),
+            (0, -3),
+            (0, 0),
+        ];
+
+        for start in [(0, 0), (3, -7)] {
+            for (dx, dy) in ends {
+                let end = (start.0 + dx, start.1 + dy);
+                let points: Vec<_> = line(start, end).collect();
+
+                assert_eq!(points.first(), Some(&start));
+                assert_eq!(points.last(), Some(&end));
+                assert_eq!(points.len() as i32, dx.abs().max(dy.abs()) + 1);
+                for step in points.windows(2) {
+                    assert_eq!(
+                        (step[1].0 - step[0].0)
+                            .abs()
+                            .max((step[1].1 - step[0].1).abs()),
+                        1
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn line_of_fire_skips_the_shooter() {
+        let map = TestMap::new(&["m...."]);
+
+        assert_eq!(
+            line_of_fire(&map, (0, 0), (4, 0)).collect::<Vec<_>>(),
+            vec![(1, 0), (2, 0), (3, 0), (4, 0)]
+        );
+        assert_eq!(line_of_fire(&map, (0, 0), (0, 0)).count(), 0);
+    }
+
+    #[test]
+    fn line_of_fire_stops_at_the_first_blocker() {
+        let map = TestMap::new(&[
+            ".....", //
+            "..m..", "...#.",
+        ]);
+
+        assert_eq!(
+            line_of_fire(&map, (0, 1), (4, 1)).collect::<Vec<_>>(),
+            vec![(1, 1), (2, 1)]
+        );
+        assert_eq!(
+            line_of_fire(&map, (0, 2), (4, 2)).last(),
+            Some((3, 2)),
+            "walls stop shots too"
+        );
+        assert_eq!(line_of_fire(&map, (0, 0), (4, 0)).last(), Some((4, 0)));
+    }
+
+    #[test]
+    fn line_of_fire_stops_at_the_map_edge() {
+        let map = TestMap::new(&["...", "..."]);
+
+        assert_eq!(
+            line_of_fire(&map, (0, 0), (5, 0)).collect::<Vec<_>>(),
+            vec![(1, 0), (2, 0)]
+        );
+    }
+}
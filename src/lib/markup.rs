@@ -0,0 +1,197 @@
+use crate::util::Color;
+
+/// Iterator returned by [parse_markup] that splits color markup into runs of text, each with the
+/// color it should be shown in, or `None` for the base color.
+pub struct MarkupIter<'a> {
+    rest: &'a str,
+    color: Option<Color>,
+}
+
+/// Parse a `{#rrggbb}` color tag at the start of a string, returning the color and the rest of the
+/// string after the tag.
+fn parse_color_tag(s: &str) -> Option<(Color, &str)> {
+    let hex = s.strip_prefix("{#")?.get(..6)?;
+
+    if !hex.bytes().all(|b| b.is_ascii_hexdigit()) || !s[8..].starts_with('}') {
+        return None;
+    }
+
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).unwrap();
+
+    Some((
+        Color {
+            r: channel(0),
+            g: channel(2),
+            b: channel(4),
+        },
+        &s[9..],
+    ))
+}
+
+impl<'a> Iterator for MarkupIter<'a> {
+    type Item = (&'a str, Option<Color>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.rest.is_empty() {
+                return None;
+            } else if let Some(tail) = self.rest.strip_prefix("{{") {
+                let text = &self.rest[..1];
+                self.rest = tail;
+                return Some((text, self.color));
+            } else if let Some(tail) = self.rest.strip_prefix("{/}") {
+                self.color = None;
+                self.rest = tail;
+            } else if let Some((color, tail)) = parse_color_tag(self.rest) {
+                self.color = Some(color);
+                self.rest = tail;
+            } else {
+                // Plain text runs up to the next brace that could start a tag.
+                let first_len = self.rest.chars().next().unwrap().len_utf8();
+                let end = self.rest[first_len..]
+                    .find('{')
+                    .map_or(self.rest.len(), |i| i + first_len);
+                let text = &self.rest[..end];
+                self.rest = &self.rest[end..];
+                return Some((text, self.color));
+            }
+        }
+    }
+}
+
+/// Parse color markup into runs of text and their colors.
+///
+/// `{#rrggbb}` switches to a color given in hexadecimal, e.g. `{#ff0000}` for red, while `{/}`
+/// switches back to the base color, which is whatever color the text would be drawn in without
+/// markup.  `{{` stands for a single `{`; any other `{` is left as-is.  The text runs returned are
+/// all slices of `s`.
+pub fn parse_markup(s: &str) -> MarkupIter<'_> {
+    MarkupIter {
+        rest: s,
+        color: None,
+    }
+}
+
+/// Remove color tags from markup, leaving just the text that would be shown.
+pub fn strip_markup(s: &str) -> String {
+    parse_markup(s).map(|(text, _)| text).collect()
+}
+
+/// Escape plain text so it shows up as-is when included in markup.
+pub fn escape_markup(s: &str) -> String {
+    s.replace('{', "{{")
+}
+
+/// Make a markup tag that switches to the given color.
+pub fn color_tag(color: Color) -> String {
+    format!("{{#{:02x}{:02x}{:02x}}}", color.r, color.g, color.b)
+}
+
+/// Wrap plain text in markup that shows it in the given color.
+pub fn colored(s: &str, color: Color) -> String {
+    format!("{}{}{{/}}", color_tag(color), escape_markup(s))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::word_wrap::word_wrap_markup;
+
+    const RED: Color = Color { r: 255, g: 0, b: 0 };
+
+    type Rgb = (u8, u8, u8);
+
+    fn runs(s: &str) -> Vec<(&str, Option<Rgb>)> {
+        parse_markup(s)
+            .map(|(text, color)| (text, color.map(|c| (c.r, c.g, c.b))))
+            .collect()
+    }
+
+    #[test]
+    fn color_tags_switch_colors() {
+        assert_eq!(
+            runs("a{#ff0000}red{#00FF7f}green{/}b"),
+            [
+                ("a", None),
+                ("red", Some((255, 0, 0))),
+                ("green", Some((0, 255, 127))),
+                ("b", None)
+            ]
+        );
+        assert_eq!(runs("{#ff0000}{/}"), []);
+        assert_eq!(
+            runs(&colored("{x}", RED)),
+            [("{", Some((255, 0, 0))), ("x}", Some((255, 0, 0)))]
+        );
+    }
+
+    #[test]
+    fn double_braces_escape_tags() {
+        assert_eq!(runs("{{#ff0000}"), [("{", None), ("#ff0000}", None)]);
+        assert_eq!(strip_markup("{{/}{{{{"), "{/}{{");
+        assert_eq!(
+            strip_markup(&escape_markup("{#ff0000} {{ {/}")),
+            "{#ff0000} {{ {/}"
+        );
+    }
+
+    #[test]
+    fn broken_tags_are_left_as_text() {
+        for s in [
+            "{",
+            "x{",
+            "{#",
+            "{#ff00",
+            "{#ff0000",
+            "{#ff0000{/}",
+            "{#gg0000}",
+            "{/",
+            "{ab}",
+        ] {
+            let expected = s.replace("{/}", "");
+
+            assert_eq!(strip_markup(s), expected, "{:?}", s);
+        }
+        assert_eq!(runs("{#ff0000"), [("{#ff0000", None)]);
+    }
+
+    #[test]
+    fn non_ascii_text_is_parsed_intact() {
+        assert_eq!(
+            runs("é{#ff0000}日本{/}ü{"),
+            [
+                ("é", None),
+                ("日本", Some((255, 0, 0))),
+                ("ü", None),
+                ("{", None)
+            ]
+        );
+        // Multi-byte characters where hex digits should be mustn't split the character.
+        for s in ["{#ééé}", "{#ff000é}", "{#ff00é}", "{#日本}"] {
+            assert_eq!(strip_markup(s), s);
+        }
+    }
+
+    #[test]
+    fn wrapped_markup_keeps_colors_and_escapes() {
+        let wrap = |s, n| word_wrap_markup(s, n).collect::<Vec<_>>();
+
+        assert_eq!(
+            wrap("{#ff0000}hello world{/}", 5),
+            ["{#ff0000}hello", "{#ff0000}world"]
+        );
+        assert_eq!(
+            wrap("{#ff0000}red {/}plain {{not} {#00ff00}green", 6),
+            ["{#ff0000}red", "plain", "{{not}", "{#00ff00}green"]
+        );
+        assert_eq!(wrap("a {{{{b {{", 3), ["a", "{{{{b", "{{"]);
+        assert_eq!(
+            wrap("{#00ff00}héllo wörld{/} ünïcode", 6),
+            ["{#00ff00}héllo", "{#00ff00}wörld", "ünïcod", "e"]
+        );
+        assert_eq!(
+            wrap("日本{#ff0000}語のテキスト", 4),
+            ["日本", "{#ff0000}語の", "{#ff0000}テキ", "{#ff0000}スト"]
+        );
+    }
+}
@@ -5,10 +5,105 @@ use std::{
 
 use super::BoundedMap;
 
-/// A trait for a map that paths can be found in using [AStarIter].
+/// A trait for a map that paths can be found in using [AStarIter] or [DijkstraMap].
 pub trait PathableMap {
     /// Returns `true` if the tile at the given coordinates is blocked.
     fn is_blocked(&self, x: i32, y: i32) -> bool;
+
+    /// Returns `true` if the tile at the given coordinates is a wall, as opposed to being blocked
+    /// by something passing through like a monster.  Only walls stop diagonal moves around them
+    /// with [Neighborhood::EightNoCornerCutting].  Defaults to [PathableMap::is_blocked].
+    fn is_wall(&self, x: i32, y: i32) -> bool {
+        self.is_blocked(x, y)
+    }
+
+    /// Returns the cost of moving onto the tile at the given coordinates in a cardinal direction.
+    /// Diagonal moves cost 1.41 times as much.  Tiles cost 100 by default.
+    ///
+    /// Costs must be at least 1: free or negative moves would let paths loop forever.
+    fn cost(&self, _x: i32, _y: i32) -> i32 {
+        100
+    }
+
+    /// Returns the lowest value that [PathableMap::cost] will return for any tile.
+    ///
+    /// [find_path] scales its estimates by this so they never exceed the real cost of a path on
+    /// maps with tiles that cost less than the default, which would make it miss cheaper paths.
+    /// Like the costs themselves, this must be at least 1.
+    fn min_cost(&self) -> i32 {
+        100
+    }
+}
+
+/// The cost of stepping onto the tile at `(x, y)` in the direction of `ADJACENT_TILES[i]`.
+fn step_cost<T: PathableMap>(map: &T, x: i32, y: i32, i: usize) -> i32 {
+    let cost = map.cost(x, y);
+
+    debug_assert!(cost >= 1, "PathableMap::cost must be at least 1");
+
+    if i >= 4 {
+        cost * 141 / 100
+    } else {
+        cost
+    }
+}
+
+/// The moves allowed from one tile to the next by [find_path] and [DijkstraMap].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Neighborhood {
+    /// Moves in the four cardinal directions only.
+    Four,
+    /// Moves in the four cardinal and four diagonal directions.
+    Eight,
+    /// Like [Neighborhood::Eight], but diagonal moves are not allowed if either of the tiles that
+    /// the move passes between is a wall; see [PathableMap::is_wall].
+    EightNoCornerCutting,
+}
+
+impl Neighborhood {
+    /// The number of entries of `ADJACENT_TILES` to consider, since cardinals come first.
+    fn num_adjacent(self) -> usize {
+        match self {
+            Neighborhood::Four => 4,
+            Neighborhood::Eight | Neighborhood::EightNoCornerCutting => 8,
+        }
+    }
+
+    /// Returns `true` if moving from `(x, y)` in the direction of `ADJACENT_TILES[i]` doesn't cut
+    /// a corner that this neighborhood forbids, where `is_wall` checks each corner tile.
+    fn allows_step(self, x: i32, y: i32, i: usize, is_wall: impl Fn(i32, i32) -> bool) -> bool {
+        if i < 4 || !matches!(self, Neighborhood::EightNoCornerCutting) {
+            true
+        } else {
+            let (dx, dy) = ADJACENT_TILES[i];
+
+            !is_wall(x + dx, y) && !is_wall(x, y + dy)
+        }
+    }
+
+    /// Estimate the cost of moving between two points with no obstacles in the way, where every
+    /// tile costs `min_cost`.  The estimate never exceeds the real cost of such a move.
+    fn estimate(self, (x1, y1): (i32, i32), (x2, y2): (i32, i32), min_cost: i32) -> i32 {
+        let x_diff = if x1 < x2 { x2 - x1 } else { x1 - x2 };
+        let y_diff = if y1 < y2 { y2 - y1 } else { y1 - y2 };
+
+        match self {
+            Neighborhood::Four => (x_diff + y_diff) * min_cost,
+            Neighborhood::Eight | Neighborhood::EightNoCornerCutting => {
+                let (low_diff, high_diff) = if x_diff < y_diff {
+                    (x_diff, y_diff)
+                } else {
+                    (y_diff, x_diff)
+                };
+
+                let straight = (high_diff - low_diff) * min_cost;
+
+                // Diagonal steps round their cost down just like step_cost does, and straight
+                // steps are undercounted by a percent to prefer axis-aligning with (x2, y2).
+                low_diff * (min_cost * 141 / 100) + straight - straight / 100
+            }
+        }
+    }
 }
 
 /// Iterator that steps through each tile in a path found by [find_path].
@@ -60,6 +155,7 @@ fn a_star<T: BoundedMap + PathableMap>(
     map: &T,
     start: (i32, i32),
     dest: (i32, i32),
+    neighborhood: Neighborhood,
     bound_pad: i32,
     came_from: &mut HashMap<(i32, i32), (i32, i32)>,
 ) -> (i32, i32) {
@@ -79,28 +175,21 @@ fn a_star<T: BoundedMap + PathableMap>(
             std::cmp::min(bounds.3, std::cmp::max(start.1, dest.1) + bound_pad),
         )
     };
-    let dist100 = |(x1, y1), (x2, y2)| {
-        let x_diff = if x1 < x2 { x2 - x1 } else { x1 - x2 };
-        let y_diff = if y1 < y2 { y2 - y1 } else { y1 - y2 };
-        let (low_diff, high_diff) = if x_diff < y_diff {
-            (x_diff, y_diff)
-        } else {
-            (y_diff, x_diff)
-        };
+    let min_cost = map.min_cost();
 
-        // Prefer axis-aligning with (x2, y2).
-        low_diff * 141 + (high_diff - low_diff) * 99
-    };
+    debug_assert!(min_cost >= 1, "PathableMap::min_cost must be at least 1");
+
+    let estimate = |a, b| neighborhood.estimate(a, b, min_cost);
     let mut closest = start;
     let mut closest_cost = 0;
-    let mut closest_dist = dist100(start, dest);
+    let mut closest_dist = estimate(start, dest);
 
     frontier.push((Reverse(0), start));
     cost_so_far.insert(start, 0);
 
     while let Some((_, current)) = frontier.pop() {
         let current_cost = *cost_so_far.get(&current).unwrap();
-        let current_dist = dist100(current, dest);
+        let current_dist = estimate(current, dest);
 
         if current_dist < closest_dist
             || (current_dist == closest_dist && current_cost < closest_cost)
@@ -114,18 +203,24 @@ fn a_star<T: BoundedMap + PathableMap>(
             break;
         }
 
-        for (i, (dx, dy)) in ADJACENT_TILES.iter().enumerate() {
+        for (i, (dx, dy)) in ADJACENT_TILES
+            .iter()
+            .enumerate()
+            .take(neighborhood.num_adjacent())
+        {
             let next_x = current.0 + dx;
             let next_y = current.1 + dy;
 
             if next_x >= min_x && next_x <= max_x && next_y >= min_y && next_y <= max_y {
                 let next = (next_x, next_y);
 
-                if next == dest || !map.is_blocked(next_x, next_y) {
-                    let next_cost = current_cost + if i >= 4 { 141 } else { 100 };
+                if (next == dest || !map.is_blocked(next_x, next_y))
+                    && neighborhood.allows_step(current.0, current.1, i, |x, y| map.is_wall(x, y))
+                {
+                    let next_cost = current_cost + step_cost(map, next_x, next_y, i);
 
                     if next_cost < *cost_so_far.get(&next).unwrap_or(&i32::MAX) {
-                        frontier.push((Reverse(next_cost + dist100(next, dest)), next));
+                        frontier.push((Reverse(next_cost + estimate(next, dest)), next));
                         came_from.insert(next, current);
                         cost_so_far.insert(next, next_cost);
                     }
@@ -137,7 +232,8 @@ fn a_star<T: BoundedMap + PathableMap>(
     closest
 }
 
-/// Find the shortest path from `start` to `dest` on the given map.
+/// Find the shortest path from `start` to `dest` on the given map, moving between tiles according
+/// to `neighborhood`.
 ///
 /// If `bound_pad` is non-zero, confine the search for the path to the rectangle created by the
 /// `start` and `dest` points plus a padding of `bound_pad` positions, otherwise search the whole
@@ -150,11 +246,12 @@ pub fn find_path<T: BoundedMap + PathableMap>(
     map: &T,
     start: (i32, i32),
     dest: (i32, i32),
+    neighborhood: Neighborhood,
     bound_pad: i32,
     fallback_closest: bool,
 ) -> AStarIter {
     let mut came_from: HashMap<(i32, i32), (i32, i32)> = HashMap::new();
-    let closest = a_star(map, start, dest, bound_pad, &mut came_from);
+    let closest = a_star(map, start, dest, neighborhood, bound_pad, &mut came_from);
 
     if closest == dest || fallback_closest {
         // Reverse the path from closest to start.
@@ -184,3 +281,463 @@ pub fn find_path<T: BoundedMap + PathableMap>(
         }
     }
 }
+
+/// A map of the movement cost from every position of a [PathableMap] to the nearest of a set of
+/// goal positions, also known as a Dijkstra map.
+///
+/// Unlike [find_path], a single Dijkstra map can serve any number of entities at once: descending
+/// it from any position approaches the nearest goal, while ascending it moves away.  Distances
+/// are measured using [PathableMap::cost], the same as [find_path].
+pub struct DijkstraMap {
+    neighborhood: Neighborhood,
+    bounds: (i32, i32, i32, i32),
+    distances: Vec<i32>,
+    walls: Vec<bool>,
+}
+
+impl DijkstraMap {
+    /// Create a Dijkstra map covering the bounds of `map`, flooded outwards from `goals` by moving
+    /// between tiles according to `neighborhood`.
+    ///
+    /// Goals are always reachable, even if they are blocked on the map, so that a blocking entity
+    /// like the player can be used as a goal.
+    pub fn new<T: BoundedMap + PathableMap>(
+        map: &T,
+        goals: &[(i32, i32)],
+        neighborhood: Neighborhood,
+    ) -> Self {
+        let mut dijkstra_map = Self {
+            neighborhood,
+            bounds: (0, 0, -1, -1),
+            distances: Vec::new(),
+            walls: Vec::new(),
+        };
+
+        dijkstra_map.rescan(map, goals);
+        dijkstra_map
+    }
+
+    /// Recalculate all distances for new goals or changes in the map, reusing existing memory.
+    pub fn rescan<T: BoundedMap + PathableMap>(&mut self, map: &T, goals: &[(i32, i32)]) {
+        let (min_x, min_y, max_x, max_y) = map.bounds();
+        let len = ((max_x - min_x + 1).max(0) * (max_y - min_y + 1).max(0)) as usize;
+
+        self.bounds = (min_x, min_y, max_x, max_y);
+        self.distances.clear();
+        self.distances.resize(len, i32::MAX);
+        self.walls.clear();
+        self.walls
+            .extend((min_y..=max_y).flat_map(|y| (min_x..=max_x).map(move |x| map.is_wall(x, y))));
+
+        for &(x, y) in goals {
+            if let Some(idx) = self.index(x, y) {
+                self.distances[idx] = 0;
+            }
+        }
+
+        self.flood(map);
+    }
+
+    /// Create a Dijkstra map for fleeing from the goals of this one.
+    ///
+    /// Distances are negated and scaled past the goals before flooding again, so descending the
+    /// resulting map heads away from the goals while still preferring escape routes over dead ends,
+    /// unlike [DijkstraMap::ascend] that will happily back into a corner.
+    pub fn flee_map<T: BoundedMap + PathableMap>(&self, map: &T) -> Self {
+        let mut flee_map = Self {
+            neighborhood: self.neighborhood,
+            bounds: self.bounds,
+            distances: self
+                .distances
+                .iter()
+                .map(|&d| if d == i32::MAX { d } else { d * -6 / 5 })
+                .collect(),
+            walls: self.walls.clone(),
+        };
+
+        flee_map.flood(map);
+        flee_map
+    }
+
+    /// Returns the distance from the given position to the nearest goal, or `None` if it is
+    /// unreachable or out of bounds.
+    pub fn distance(&self, x: i32, y: i32) -> Option<i32> {
+        self.index(x, y)
+            .map(|idx| self.distances[idx])
+            .filter(|&d| d != i32::MAX)
+    }
+
+    /// Returns the adjacent position that is the closest to the nearest goal, or `None` if there
+    /// is no reachable adjacent position closer than the given position.
+    pub fn descend(&self, x: i32, y: i32) -> Option<(i32, i32)> {
+        let mut best = None;
+        let mut best_dist = self.distance(x, y).unwrap_or(i32::MAX);
+
+        for (i, (dx, dy)) in ADJACENT_TILES
+            .iter()
+            .enumerate()
+            .take(self.neighborhood.num_adjacent())
+        {
+            if let Some(dist) = self.distance(x + dx, y + dy) {
+                if dist < best_dist && self.allows_step(x, y, i) {
+                    best = Some((x + dx, y + dy));
+                    best_dist = dist;
+                }
+            }
+        }
+
+        best
+    }
+
+    /// Returns the adjacent position that is the farthest from the nearest goal, or `None` if
+    /// there is no reachable adjacent position farther than the given position.
+    pub fn ascend(&self, x: i32, y: i32) -> Option<(i32, i32)> {
+        let mut best = None;
+        let mut best_dist = self.distance(x, y).unwrap_or(i32::MIN);
+
+        for (i, (dx, dy)) in ADJACENT_TILES
+            .iter()
+            .enumerate()
+            .take(self.neighborhood.num_adjacent())
+        {
+            if let Some(dist) = self.distance(x + dx, y + dy) {
+                if dist > best_dist && self.allows_step(x, y, i) {
+                    best = Some((x + dx, y + dy));
+                    best_dist = dist;
+                }
+            }
+        }
+
+        best
+    }
+
+    /// Returns `true` if a step from `(x, y)` doesn't cut a forbidden corner around the walls that
+    /// were on the map when it was last scanned.
+    fn allows_step(&self, x: i32, y: i32, i: usize) -> bool {
+        self.neighborhood.allows_step(x, y, i, |x, y| {
+            self.index(x, y).map(|idx| self.walls[idx]).unwrap_or(true)
+        })
+    }
+
+    fn index(&self, x: i32, y: i32) -> Option<usize> {
+        let (min_x, min_y, max_x, max_y) = self.bounds;
+
+        if x >= min_x && x <= max_x && y >= min_y && y <= max_y {
+            Some(((y - min_y) * (max_x - min_x + 1) + (x - min_x)) as usize)
+        } else {
+            None
+        }
+    }
+
+    /// Spread the distances of all reachable positions out to every position reachable from them.
+    fn flood<T: PathableMap>(&mut self, map: &T) {
+        let (min_x, min_y, max_x, _) = self.bounds;
+        let width = max_x - min_x + 1;
+        // (priority, (x, y))
+        let mut frontier: BinaryHeap<(Reverse<i32>, (i32, i32))> = self
+            .distances
+            .iter()
+            .enumerate()
+            .filter(|(_, &d)| d != i32::MAX)
+            .map(|(idx, &d)| {
+                let idx = idx as i32;
+                (Reverse(d), (min_x + idx % width, min_y + idx / width))
+            })
+            .collect();
+
+        while let Some((Reverse(current_cost), current)) = frontier.pop() {
+            if current_cost > self.distances[self.index(current.0, current.1).unwrap()] {
+                continue;
+            }
+
+            for (i, (dx, dy)) in ADJACENT_TILES
+                .iter()
+                .enumerate()
+                .take(self.neighborhood.num_adjacent())
+            {
+                let next_x = current.0 + dx;
+                let next_y = current.1 + dy;
+
+                if let Some(next_idx) = self.index(next_x, next_y) {
+                    if !map.is_blocked(next_x, next_y)
+                        && self
+                            .neighborhood
+                            .allows_step(current.0, current.1, i, |x, y| map.is_wall(x, y))
+                    {
+                        let next_cost = current_cost + step_cost(map, next_x, next_y, i);
+
+                        if next_cost < self.distances[next_idx] {
+                            frontier.push((Reverse(next_cost), (next_x, next_y)));
+                            self.distances[next_idx] = next_cost;
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{seq::SliceRandom, Rng, SeedableRng};
+    use rand_xoshiro::Xoshiro128PlusPlus;
+
+    use super::*;
+
+    /// A map drawn as rows of characters, where `#` is a wall, `.` is a floor tile, `m` is a floor
+    /// tile blocked by a monster and digits are floor tiles that cost `digit_cost` times their
+    /// value to move onto, so `1` is cheaper than `.`.
+    struct TestMap {
+        width: i32,
+        height: i32,
+        tiles: Vec<char>,
+        digit_cost: i32,
+    }
+
+    impl TestMap {
+        fn new(rows: &[&str]) -> Self {
+            Self::with_digit_cost(rows, 25)
+        }
+
+        fn with_digit_cost(rows: &[&str], digit_cost: i32) -> Self {
+            Self {
+                width: rows[0].len() as i32,
+                height: rows.len() as i32,
+                tiles: rows.iter().flat_map(|row| row.chars()).collect(),
+                digit_cost,
+            }
+        }
+
+        fn tile(&self, x: i32, y: i32) -> char {
+            self.tiles[(y * self.width + x) as usize]
+        }
+    }
+
+    impl BoundedMap for TestMap {
+        fn bounds(&self) -> (i32, i32, i32, i32) {
+            (0, 0, self.width - 1, self.height - 1)
+        }
+    }
+
+    impl PathableMap for TestMap {
+        fn is_blocked(&self, x: i32, y: i32) -> bool {
+            matches!(self.tile(x, y), '#' | 'm')
+        }
+
+        fn is_wall(&self, x: i32, y: i32) -> bool {
+            self.tile(x, y) == '#'
+        }
+
+        fn cost(&self, x: i32, y: i32) -> i32 {
+            self.tile(x, y)
+                .to_digit(10)
+                .map_or(100, |d| d as i32 * self.digit_cost)
+        }
+
+        fn min_cost(&self) -> i32 {
+            (0..self.height)
+                .flat_map(|y| (0..self.width).map(move |x| (x, y)))
+                .map(|(x, y)| self.cost(x, y))
+                .min()
+                .unwrap()
+        }
+    }
+
+    /// Total cost of following a path from its first position.
+    fn path_cost(map: &TestMap, path: &[(i32, i32)]) -> i32 {
+        path.windows(2)
+            .map(|step| {
+                let (dx, dy) = (step[1].0 - step[0].0, step[1].1 - step[0].1);
+                let i = ADJACENT_TILES.iter().position(|&d| d == (dx, dy)).unwrap();
+
+                step_cost(map, step[1].0, step[1].1, i)
+            })
+            .sum()
+    }
+
+    /// Descend a Dijkstra map from a starting position as far as it goes.
+    fn descend_all(dijkstra_map: &DijkstraMap, start: (i32, i32)) -> Vec<(i32, i32)> {
+        let mut path = vec![start];
+
+        while let Some(next) = dijkstra_map.descend(path[path.len() - 1].0, path[path.len() - 1].1)
+        {
+            path.push(next);
+        }
+
+        path
+    }
+
+    #[test]
+    fn dijkstra_distances_measure_to_nearest_goal() {
+        let map = TestMap::new(&[
+            ".......", //
+            ".###...", ".......",
+        ]);
+        let dijkstra_map = DijkstraMap::new(&map, &[(0, 0), (6, 2)], Neighborhood::Four);
+
+        assert_eq!(dijkstra_map.distance(0, 0), Some(0));
+        assert_eq!(dijkstra_map.distance(6, 2), Some(0));
+        assert_eq!(dijkstra_map.distance(2, 0), Some(200));
+        assert_eq!(dijkstra_map.distance(3, 2), Some(300));
+        assert_eq!(dijkstra_map.distance(2, 1), None);
+        assert_eq!(dijkstra_map.distance(7, 0), None);
+    }
+
+    #[test]
+    fn descending_dijkstra_map_reaches_a_goal() {
+        let map = TestMap::new(&[
+            "........", //
+            ".######.", ".#....#.", ".#.##.#.", "........",
+        ]);
+        let goals = [(3, 2)];
+
+        for neighborhood in [Neighborhood::Four, Neighborhood::Eight] {
+            let dijkstra_map = DijkstraMap::new(&map, &goals, neighborhood);
+            let path = descend_all(&dijkstra_map, (0, 0));
+
+            assert_eq!(path[path.len() - 1], goals[0]);
+            for step in path.windows(2) {
+                let (a, b) = (step[0], step[1]);
+                assert!(dijkstra_map.distance(b.0, b.1) < dijkstra_map.distance(a.0, a.1));
+                assert!(!map.is_blocked(b.0, b.1));
+            }
+        }
+    }
+
+    #[test]
+    fn flee_map_leads_away_from_goals() {
+        let map = TestMap::new(&["............"]);
+        let dijkstra_map = DijkstraMap::new(&map, &[(2, 0)], Neighborhood::Four);
+        let flee_map = dijkstra_map.flee_map(&map);
+
+        // Fleeing from beside the goal runs down the long side of the corridor to its far end.
+        assert_eq!(flee_map.descend(3, 0), Some((4, 0)));
+        assert_eq!(descend_all(&flee_map, (3, 0)).last(), Some(&(11, 0)));
+
+        // Fleeing never steps onto a goal.
+        for x in 0..12 {
+            assert_ne!(flee_map.descend(x, 0), Some((2, 0)));
+        }
+    }
+
+    #[test]
+    fn flee_map_runs_around_loops() {
+        let map = TestMap::new(&[
+            "#########", //
+            "#.......#",
+            "#.#####.#",
+            "#.......#",
+            "#########",
+        ]);
+        let dijkstra_map = DijkstraMap::new(&map, &[(3, 3)], Neighborhood::Four);
+        let flee_map = dijkstra_map.flee_map(&map);
+
+        // Starting between the goal and a corner, fleeing goes through the corner and on around
+        // the loop to the far side instead of stopping in the corner.
+        let path = descend_all(&flee_map, (2, 3));
+
+        assert_eq!(path[1], (1, 3));
+        assert_eq!(path.last(), Some(&(5, 1)));
+    }
+
+    #[test]
+    fn weighted_paths_go_around_expensive_tiles() {
+        let map = TestMap::new(&[
+            ".....", //
+            ".999.", ".....",
+        ]);
+        let path: Vec<_> = find_path(&map, (0, 1), (4, 1), Neighborhood::Four, 0, false).collect();
+
+        assert_eq!(path.first(), Some(&(0, 1)));
+        assert_eq!(path.last(), Some(&(4, 1)));
+        assert!(path.iter().all(|&(x, y)| map.tile(x, y) != '9'));
+        assert_eq!(path_cost(&map, &path), 600);
+    }
+
+    /// Check that paths found on random maps cost the same as the distances of Dijkstra maps.
+    fn check_cheapest_paths(digit_cost: i32, seed: u64) {
+        let mut rng = Xoshiro128PlusPlus::seed_from_u64(seed);
+        let neighborhoods = [
+            Neighborhood::Four,
+            Neighborhood::Eight,
+            Neighborhood::EightNoCornerCutting,
+        ];
+
+        for _ in 0..500 {
+            let rows: Vec<String> = (0..8)
+                .map(|_| {
+                    (0..10)
+                        .map(|_| *"##..123456789".as_bytes().choose(&mut rng).unwrap() as char)
+                        .collect()
+                })
+                .collect();
+            let rows: Vec<_> = rows.iter().map(String::as_str).collect();
+            let map = TestMap::with_digit_cost(&rows, digit_cost);
+            let mut random_floor = || loop {
+                let pos = (rng.gen_range(0..10), rng.gen_range(0..8));
+                if !map.is_blocked(pos.0, pos.1) {
+                    return pos;
+                }
+            };
+            let (start, dest) = (random_floor(), random_floor());
+
+            for &neighborhood in &neighborhoods {
+                let dijkstra_map = DijkstraMap::new(&map, &[start], neighborhood);
+                let path: Vec<_> = find_path(&map, start, dest, neighborhood, 0, false).collect();
+
+                match dijkstra_map.distance(dest.0, dest.1) {
+                    Some(dist) => {
+                        assert_eq!(path.first(), Some(&start), "{:?}", rows);
+                        assert_eq!(path.last(), Some(&dest), "{:?}", rows);
+                        assert_eq!(path_cost(&map, &path), dist, "{:?}", rows);
+                    }
+                    None => assert!(path.is_empty(), "{:?}", rows),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn weighted_paths_are_as_cheap_as_possible() {
+        check_cheapest_paths(25, 0);
+    }
+
+    #[test]
+    fn cheap_tiles_keep_paths_as_cheap_as_possible() {
+        // Tiles costing 1 or 7 per digit round diagonal costs down by a large fraction of a tile,
+        // which the estimates of find_path must account for.
+        check_cheapest_paths(1, 1);
+        check_cheapest_paths(7, 2);
+    }
+
+    #[test]
+    fn corner_cutting_only_checks_walls() {
+        let map = TestMap::new(&[
+            "...", //
+            "m.#", "...",
+        ]);
+
+        for neighborhood in [Neighborhood::Eight, Neighborhood::EightNoCornerCutting] {
+            let cuts_corners = neighborhood == Neighborhood::Eight;
+            let path_len =
+                |start, dest| find_path(&map, start, dest, neighborhood, 0, false).count();
+            let to_center = DijkstraMap::new(&map, &[(1, 1)], neighborhood);
+            let to_corner = DijkstraMap::new(&map, &[(2, 2)], neighborhood);
+
+            // Moving diagonally past a monster is always fine.
+            assert_eq!(path_len((0, 0), (1, 1)), 2);
+            assert_eq!(to_center.descend(0, 0), Some((1, 1)));
+
+            // Moving diagonally past a wall is only allowed when cutting corners.
+            if cuts_corners {
+                assert_eq!(path_len((1, 1), (2, 2)), 2);
+                assert_eq!(to_corner.distance(1, 1), Some(141));
+                assert_eq!(to_corner.descend(1, 1), Some((2, 2)));
+            } else {
+                assert_eq!(path_len((1, 1), (2, 2)), 3);
+                assert_eq!(to_corner.distance(1, 1), Some(200));
+                assert_eq!(to_corner.descend(1, 1), Some((1, 2)));
+            }
+        }
+    }
+}
@@ -2,15 +2,23 @@ use sdl2::{
     event::{Event, WindowEvent},
     pixels::Color as Sdl2Color,
     rect::Rect,
+    video::{FullscreenType, Window},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    time::{Duration, Instant, SystemTime},
 };
-use std::time::{Duration, Instant};
 
 use crate::{
     input_buffer::InputBuffer,
-    tilegrid::{Symbol, TileGridLayer, Tileset, TilesetInfo},
-    util::Size,
+    tilegrid::{save_screenshot, Symbol, TileGridLayer, Tileset, TilesetImage, TilesetInfo},
+    util::{Position, Size},
 };
 
+/// How often to check tile images for changes when hot reloading tilesets.
+const HOT_RELOAD_INTERVAL: Duration = Duration::from_secs(1);
+
 /// Return value for `update` callback sent into [run] that controls the main event loop.
 pub enum RunControl {
     /// Wait for an event before calling `update` again.
@@ -21,6 +29,17 @@ pub enum RunControl {
     Quit,
 }
 
+/// How the window created by [run] is shown.
+#[derive(Clone, Copy, Eq, PartialEq, Deserialize, Serialize)]
+pub enum WindowMode {
+    /// A resizable window with a border.
+    Windowed,
+    /// A borderless window covering the whole screen at the desktop resolution.
+    Borderless,
+    /// Exclusive fullscreen, which changes the display resolution to match the window size.
+    Fullscreen,
+}
+
 /// Window and event loop settings for [run].
 pub struct RunSettings<Y: Symbol> {
     /// Window title.
@@ -29,10 +48,109 @@ pub struct RunSettings<Y: Symbol> {
     pub window_size: Size,
     /// Minimum pixel width and height of the window.
     pub min_window_size: Size,
+    /// Whether to start windowed or fullscreen; see [crate::InputBuffer::request_window_mode] to
+    /// change it later.
+    pub window_mode: WindowMode,
+    /// Display TileGrids scaled up by the largest whole number that still leaves at least
+    /// `min_window_size` pixels of space for them, keeping tiles pixel-perfect on large screens; see
+    /// [crate::InputBuffer::request_integer_scale] to change it later.
+    pub integer_scale: bool,
     /// Frames per second.
     pub fps: u32,
     /// Tilesets to draw TileGrids with.
     pub tileset_infos: Vec<TilesetInfo<Y>>,
+    /// Watch the files of tile images loaded from paths and reload their tilesets when they change.
+    /// Meant for use during development.  Ignored by [crate::run_headless].
+    pub hot_reload_tilesets: bool,
+}
+
+/// Tracks the modification times of tile image files to reload their tilesets when they change.
+struct TilesetWatcher<Y: Symbol> {
+    tileset_infos: Vec<TilesetInfo<Y>>,
+    modified: Vec<Option<SystemTime>>,
+    last_check: Instant,
+}
+
+impl<Y: Symbol> TilesetWatcher<Y> {
+    fn modified_time(tileset_info: &TilesetInfo<Y>) -> Option<SystemTime> {
+        match &tileset_info.image {
+            TilesetImage::Path(path) => fs::metadata(path).and_then(|m| m.modified()).ok(),
+            TilesetImage::Bytes(_) => None,
+        }
+    }
+
+    fn new(tileset_infos: Vec<TilesetInfo<Y>>) -> Self {
+        let modified = tileset_infos.iter().map(Self::modified_time).collect();
+
+        Self {
+            tileset_infos,
+            modified,
+            last_check: Instant::now(),
+        }
+    }
+
+    /// Reload tilesets whose tile images have changed since the last check, if it's time to check
+    /// again, and flag TileGrids using them to be redrawn.
+    fn check<'s>(&mut self, tilesets: &mut [Tileset<'s, Y>], layers: &mut [TileGridLayer<Y>]) {
+        if self.last_check.elapsed() < HOT_RELOAD_INTERVAL {
+            return;
+        }
+        self.last_check = Instant::now();
+
+        for (i, tileset_info) in self.tileset_infos.iter().enumerate() {
+            let modified = Self::modified_time(tileset_info);
+
+            if modified.is_some() && modified != self.modified[i] {
+                // Retry later if the image can't be loaded, e.g. if it's only partly written.
+                match Tileset::load(tileset_info.clone()) {
+                    Ok(tileset) => {
+                        tilesets[i] = tileset;
+                        self.modified[i] = modified;
+
+                        for layer in layers.iter_mut() {
+                            for grid in layer.grids.iter_mut().filter(|g| g.tileset() == i) {
+                                grid.flag_texture_recreate();
+                            }
+                        }
+                    }
+                    Err(e) => eprintln!("Warning: failed to reload tileset {}: {}", i, e),
+                }
+            }
+        }
+    }
+}
+
+/// Switch the window to the given mode, warning if that fails.
+fn set_window_mode(window: &mut Window, window_mode: WindowMode) {
+    let fullscreen_type = match window_mode {
+        WindowMode::Windowed => FullscreenType::Off,
+        WindowMode::Borderless => FullscreenType::Desktop,
+        WindowMode::Fullscreen => FullscreenType::True,
+    };
+
+    if let Err(e) = window.set_fullscreen(fullscreen_type) {
+        eprintln!("Warning: failed to change window mode: {}", e);
+    }
+}
+
+/// Whole number scale to display TileGrids at for a window size, or 1 if integer scaling is off.
+fn display_scale(window_size: (u32, u32), min_window_size: Size, integer_scale: bool) -> u32 {
+    if integer_scale {
+        (window_size.0 / min_window_size.w)
+            .min(window_size.1 / min_window_size.h)
+            .max(1)
+    } else {
+        1
+    }
+}
+
+/// Top-left corner of the area of the window that TileGrids are displayed in, splitting any margin
+/// left over from scaling evenly between both sides.
+fn display_offset(window_size: (u32, u32), view_size: Size, scale: u32) -> Position {
+    Position {
+        x: (window_size.0.saturating_sub(view_size.w * scale) / 2) as i32,
+        y: (window_size.1.saturating_sub(view_size.h * scale) / 2) as i32,
+    }
 }
 
 /// Create a window and run a main event loop that calls `update` repeatedly.
@@ -45,6 +163,7 @@ where
 {
     let sdl_context = sdl2::init().unwrap();
     let video_subsystem = sdl_context.video().unwrap();
+    let game_controller_subsystem = sdl_context.game_controller().unwrap();
     let _image_context = sdl2::image::init(sdl2::image::InitFlag::PNG).unwrap();
 
     assert!(settings.window_size.w > 0 && settings.window_size.w <= i32::MAX as u32);
@@ -69,12 +188,24 @@ where
         .set_minimum_size(settings.window_size.w, settings.window_size.h)
         .unwrap();
 
+    set_window_mode(&mut window, settings.window_mode);
+
     let mut canvas = window.into_canvas().build().unwrap();
     let texture_creator = canvas.texture_creator();
     let mut event_pump = sdl_context.event_pump().unwrap();
 
+    // Text input is only turned on when requested via the InputBuffer.
+    let text_input = video_subsystem.text_input();
+    text_input.stop();
+
     assert!(!settings.tileset_infos.is_empty());
 
+    let mut tileset_watcher = if settings.hot_reload_tilesets {
+        Some(TilesetWatcher::new(settings.tileset_infos.clone()))
+    } else {
+        None
+    };
+
     let mut tilesets = Vec::with_capacity(settings.tileset_infos.len());
     for tileset_info in settings.tileset_infos {
         tilesets.push(Tileset::new(tileset_info));
@@ -82,8 +213,10 @@ where
 
     let mut window_size = canvas.output_size().unwrap();
     let mut window_rect = Rect::new(0, 0, window_size.0, window_size.1);
+    let mut integer_scale = settings.integer_scale;
     let mut layers: Vec<TileGridLayer<Y>> = Vec::new();
     let mut inputs = InputBuffer::new();
+    let mut controllers = Vec::new();
 
     let mut mouse_shown = true;
     let mut active_update = true;
@@ -105,13 +238,31 @@ where
     while !done {
         let mut new_mouse_shown = None;
 
-        // Wait for an event if waiting is requested.
-        let waited_event = if !active_update && !inputs.more_inputs() {
-            Some(event_pump.wait_event())
+        // Wait for an event if waiting is requested, but not so long that animation frames are
+        // held up, or that tile image changes go unnoticed when hot reloading tilesets.
+        let waiting = !active_update && !inputs.more_inputs();
+        let animating = layers
+            .iter()
+            .flat_map(|l| &l.grids)
+            .any(|g| g.is_animating());
+        let waited_event = if waiting {
+            if animating {
+                event_pump.wait_event_timeout(frame_time.as_millis() as u32)
+            } else if tileset_watcher.is_some() {
+                event_pump.wait_event_timeout(HOT_RELOAD_INTERVAL.as_millis() as u32)
+            } else {
+                Some(event_pump.wait_event())
+            }
         } else {
             None
         };
 
+        let wait_timed_out = waiting && waited_event.is_none();
+
+        if let Some(tileset_watcher) = &mut tileset_watcher {
+            tileset_watcher.check(&mut tilesets, &mut layers);
+        }
+
         // Poll for additional events and handle all events.
         for event in waited_event.into_iter().chain(event_pump.poll_iter()) {
             match event {
@@ -121,7 +272,20 @@ where
                 } => {
                     window_size = (w as u32, h as u32);
                 }
-                Event::KeyDown { .. } | Event::KeyUp { .. } => new_mouse_shown = Some(false),
+                Event::ControllerDeviceAdded { which, .. } => {
+                    // Keep the controller open so that its events keep coming in.
+                    match game_controller_subsystem.open(which) {
+                        Ok(controller) => controllers.push(controller),
+                        Err(e) => eprintln!("Warning: failed to open game controller: {}", e),
+                    }
+                }
+                Event::ControllerDeviceRemoved { which, .. } => {
+                    controllers.retain(|c| c.instance_id() != which);
+                }
+                Event::KeyDown { .. }
+                | Event::KeyUp { .. }
+                | Event::ControllerButtonDown { .. }
+                | Event::ControllerButtonUp { .. } => new_mouse_shown = Some(false),
                 Event::MouseMotion { .. }
                 | Event::MouseButtonDown { .. }
                 | Event::MouseButtonUp { .. }
@@ -162,6 +326,16 @@ where
             window_size.1 = settings.min_window_size.h;
         }
 
+        // TileGrids are laid out in scaled-down pixels that are scaled back up when displayed.
+        let scale = display_scale(window_size, settings.min_window_size, integer_scale);
+        let view_size = Size {
+            w: window_size.0 / scale,
+            h: window_size.1 / scale,
+        };
+        let offset = display_offset(window_size, view_size, scale);
+
+        inputs.set_mouse_transform(offset, scale);
+
         // Perform update(s).
         let start = previous;
         if active_update {
@@ -177,7 +351,7 @@ where
                     update_count += 1;
                 }
 
-                match update(&mut inputs, &mut layers, &tilesets[..], window_size.into()) {
+                match update(&mut inputs, &mut layers, &tilesets[..], view_size) {
                     RunControl::Update => lag -= frame_time,
                     RunControl::WaitForEvent => {
                         active_update = false;
@@ -195,7 +369,7 @@ where
                     lag = Duration::new(0, 0);
                 }
             }
-        } else {
+        } else if !wait_timed_out {
             previous = Instant::now();
 
             #[cfg(feature = "fps")]
@@ -204,7 +378,7 @@ where
             }
 
             // Update once in response to events.
-            match update(&mut inputs, &mut layers, &tilesets[..], window_size.into()) {
+            match update(&mut inputs, &mut layers, &tilesets[..], view_size) {
                 RunControl::WaitForEvent => {}
                 RunControl::Update => {
                     active_update = true;
@@ -214,17 +388,55 @@ where
             }
         }
 
+        // Turn text input on or off as requested.
+        if inputs.is_text_input_active() != text_input.is_active() {
+            if inputs.is_text_input_active() {
+                text_input.start();
+            } else {
+                text_input.stop();
+            }
+        }
+
+        // Change the window mode and integer scaling as requested.
+        let mut display_changed = false;
+        if let Some(window_mode) = inputs.take_window_mode_request() {
+            set_window_mode(canvas.window_mut(), window_mode);
+            window_size = canvas.output_size().unwrap();
+            display_changed = true;
+        }
+        if let Some(new_integer_scale) = inputs.take_integer_scale_request() {
+            display_changed |= integer_scale != new_integer_scale;
+            integer_scale = new_integer_scale;
+        }
+        if display_changed && !done {
+            // Update again so that TileGrids are laid out for the new display size.
+            active_update = true;
+            lag = frame_time;
+        }
+
         // Skip rendering if we're going to exit anyway.
         if done {
             break;
         }
 
+        // Clear the whole window, including any margins left over by integer scaling.
         window_rect.set_width(window_size.0);
         window_rect.set_height(window_size.1);
+        canvas.set_scale(1.0, 1.0).unwrap();
+        canvas.set_viewport(None);
         canvas.set_clip_rect(window_rect);
         canvas.set_draw_color(Sdl2Color::BLACK);
         canvas.clear();
 
+        // Center the display area; the viewport is set in window pixels before scaling up.
+        canvas.set_viewport(Rect::new(
+            offset.x,
+            offset.y,
+            view_size.w * scale,
+            view_size.h * scale,
+        ));
+        canvas.set_scale(scale as f32, scale as f32).unwrap();
+
         // Display the grids, starting from the lowest visible layer.
         let start_layer_draw_from = layers.iter().rposition(|l| !l.draw_behind).unwrap_or(0);
 
@@ -234,8 +446,28 @@ where
             }
         }
 
+        // Save a screenshot of the frame if one was requested during the update.
+        if let Some(path) = inputs.take_screenshot_request() {
+            // Pixels are read from the viewport, so widen it back out to the whole window.
+            canvas.set_viewport(None);
+
+            if let Err(e) = save_screenshot(&canvas, &path) {
+                eprintln!(
+                    "Warning: Failed to save screenshot to {}: {}",
+                    path.display(),
+                    e
+                );
+            }
+        }
+
         canvas.present();
 
+        for layer in &mut layers {
+            for grid in &mut layer.grids {
+                grid.advance_animations();
+            }
+        }
+
         // Discard any current input to make way for the next one.
         inputs.clear_input();
 
@@ -265,3 +497,30 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn integer_scaling_centers_the_display() {
+        let min_size = Size { w: 640, h: 480 };
+
+        assert_eq!(display_scale((1300, 1000), min_size, false), 1);
+        assert_eq!(display_scale((1300, 1000), min_size, true), 2);
+        assert_eq!(display_scale((1300, 900), min_size, true), 1);
+        assert_eq!(display_scale((600, 400), min_size, true), 1);
+
+        // Layouts get the window size divided by the scale, leaving up to a pixel less than the
+        // scale to spare on each axis.
+        let view_size = Size {
+            w: 1301 / 3,
+            h: 1000 / 3,
+        };
+        let offset = display_offset((1301, 1000), view_size, 3);
+
+        assert_eq!((offset.x, offset.y), (1, 0));
+        let offset = display_offset((1300, 1000), Size { w: 650, h: 500 }, 2);
+        assert_eq!((offset.x, offset.y), (0, 0));
+    }
+}
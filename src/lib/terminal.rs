@@ -0,0 +1,664 @@
+use crossterm::{
+    cursor::{Hide, MoveTo, Show},
+    event::{self, Event, KeyCode, KeyEvent, KeyModifiers},
+    queue,
+    style::{Color as TermColor, Print, ResetColor, SetBackgroundColor, SetForegroundColor},
+    terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use sdl2::keyboard::Keycode;
+use std::{
+    io::{self, BufWriter, Stdout, Write},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    input_buffer::{InputBuffer, InputEvent},
+    run::{RunControl, RunSettings},
+    tilegrid::{Symbol, TileGridLayer, Tileset},
+    util::{Color, Size},
+};
+
+/// A character cell of the terminal along with its foreground and background colors.
+type TermCell = (char, Color, Color);
+
+const BLANK: TermCell = (' ', Color::WHITE, Color::BLACK);
+
+/// Puts the terminal into raw mode on an alternate screen, and puts it back the way it was when
+/// dropped, even if the main loop panics.
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn enter(out: &mut impl Write) -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        queue!(out, EnterAlternateScreen, Hide)?;
+        out.flush()?;
+
+        Ok(Self)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let mut out = io::stdout();
+        let _ = queue!(out, ResetColor, Show, LeaveAlternateScreen);
+        let _ = out.flush();
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// Multiply two colors together like a texture color mod does.
+fn color_mod(c: Color, m: Color) -> Color {
+    Color {
+        r: (c.r as u16 * m.r as u16 / 255) as u8,
+        g: (c.g as u16 * m.g as u16 / 255) as u8,
+        b: (c.b as u16 * m.b as u16 / 255) as u8,
+    }
+}
+
+/// Map a clipping rectangle span in pixels to a range of terminal cells along one axis.
+fn clip_span(pos: i32, size: u32, unit: u32, limit: i32) -> (i32, i32) {
+    let start = (pos as f32 / unit as f32).round() as i32;
+    let end = ((pos + size as i32) as f32 / unit as f32).round() as i32;
+
+    (start.max(0), end.min(limit))
+}
+
+/// Find the terminal cell that the first cell of a TileGrid lands on along one axis.
+///
+/// Cells of TileGrids are shown one per terminal cell, even if their tiles are bigger or smaller
+/// than `unit`, so the grid is anchored at the center of its clipping rectangle to keep whatever
+/// is centered in the window centered in the terminal.
+fn grid_origin(pos: i32, size: u32, offset: i32, tile: u32, unit: u32) -> i32 {
+    let center = pos as f32 + size as f32 / 2.0;
+
+    (center / unit as f32 - (center - (pos + offset) as f32) / tile as f32).round() as i32
+}
+
+/// Compose the visible TileGrids of the layers into terminal cells, bottom-up.
+fn compose<Y: Symbol>(
+    layers: &[TileGridLayer<Y>],
+    tilesets: &[Tileset<Y>],
+    unit: Size,
+    (cols, rows): (u16, u16),
+    frame: &mut Vec<TermCell>,
+) {
+    let (cols, rows) = (cols as i32, rows as i32);
+
+    frame.clear();
+    frame.resize((cols * rows) as usize, BLANK);
+
+    for grid in layers.iter().flat_map(|l| &l.grids) {
+        let view = &grid.view;
+
+        if !view.visible || view.zoom == 0 {
+            continue;
+        }
+
+        let tileset = &tilesets[grid.tileset()];
+        let (x0, x1) = clip_span(view.pos.x, view.size.w, unit.w, cols);
+        let (y0, y1) = clip_span(view.pos.y, view.size.h, unit.h, rows);
+        let origin_x = grid_origin(
+            view.pos.x,
+            view.size.w,
+            view.dx,
+            tileset.tile_width() * view.zoom,
+            unit.w,
+        );
+        let origin_y = grid_origin(
+            view.pos.y,
+            view.size.h,
+            view.dy,
+            tileset.tile_height() * view.zoom,
+            unit.h,
+        );
+
+        for y in y0..y1 {
+            for x in x0..x1 {
+                let cell = &mut frame[(y * cols + x) as usize];
+
+                if let Some((ch, fg, bg)) = grid.get_cell_text((x - origin_x, y - origin_y)) {
+                    *cell = (
+                        if ch.is_control() { ' ' } else { ch },
+                        color_mod(fg, view.color_mod),
+                        color_mod(bg, view.color_mod),
+                    );
+                } else if let Some(clear_color) = view.clear_color {
+                    *cell = (' ', clear_color, clear_color);
+                }
+            }
+        }
+    }
+}
+
+fn term_color(c: Color) -> TermColor {
+    TermColor::Rgb {
+        r: c.r,
+        g: c.g,
+        b: c.b,
+    }
+}
+
+/// Write the rows of `frame` that differ from `shown` to the terminal, then remember them.
+fn present(
+    out: &mut BufWriter<Stdout>,
+    frame: &[TermCell],
+    shown: &mut Vec<TermCell>,
+    cols: u16,
+) -> io::Result<()> {
+    if cols == 0 {
+        return Ok(());
+    }
+
+    if shown.len() != frame.len() {
+        shown.clear();
+        queue!(out, Clear(ClearType::All))?;
+    }
+
+    for (y, row) in frame.chunks(cols as usize).enumerate() {
+        let start = y * cols as usize;
+
+        if shown.get(start..start + row.len()) == Some(row) {
+            continue;
+        }
+
+        let mut colors = None;
+
+        queue!(out, MoveTo(0, y as u16))?;
+        for &(ch, fg, bg) in row {
+            if colors != Some((fg, bg)) {
+                queue!(out, SetForegroundColor(term_color(fg)))?;
+                queue!(out, SetBackgroundColor(term_color(bg)))?;
+                colors = Some((fg, bg));
+            }
+            queue!(out, Print(ch))?;
+        }
+    }
+
+    out.flush()?;
+
+    shown.clear();
+    shown.extend_from_slice(frame);
+
+    Ok(())
+}
+
+/// Map a non-character terminal key to a keycode.
+fn special_keycode(code: KeyCode) -> Option<Keycode> {
+    Some(match code {
+        KeyCode::Backspace => Keycode::Backspace,
+        KeyCode::Enter => Keycode::Return,
+        KeyCode::Left => Keycode::Left,
+        KeyCode::Right => Keycode::Right,
+        KeyCode::Up => Keycode::Up,
+        KeyCode::Down => Keycode::Down,
+        KeyCode::Home => Keycode::Home,
+        KeyCode::End => Keycode::End,
+        KeyCode::PageUp => Keycode::PageUp,
+        KeyCode::PageDown => Keycode::PageDown,
+        KeyCode::Tab | KeyCode::BackTab => Keycode::Tab,
+        KeyCode::Delete => Keycode::Delete,
+        KeyCode::Insert => Keycode::Insert,
+        KeyCode::Esc => Keycode::Escape,
+        KeyCode::F(1) => Keycode::F1,
+        KeyCode::F(2) => Keycode::F2,
+        KeyCode::F(3) => Keycode::F3,
+        KeyCode::F(4) => Keycode::F4,
+        KeyCode::F(5) => Keycode::F5,
+        KeyCode::F(6) => Keycode::F6,
+        KeyCode::F(7) => Keycode::F7,
+        KeyCode::F(8) => Keycode::F8,
+        KeyCode::F(9) => Keycode::F9,
+        KeyCode::F(10) => Keycode::F10,
+        KeyCode::F(11) => Keycode::F11,
+        KeyCode::F(12) => Keycode::F12,
+        _ => return None,
+    })
+}
+
+/// Turn a terminal key event into the input events an SDL window would produce for it.
+///
+/// Terminals only report key presses, so each key is pressed and released at once, wrapped in
+/// presses and releases of any modifier keys held with it.  Shifted symbols arrive as their own
+/// characters, e.g. '>' instead of Shift+'.', so only letters carry Shift.
+fn handle_key(inputs: &mut InputBuffer, key: KeyEvent) {
+    let mut mods = key.modifiers;
+
+    if key.code == KeyCode::Char('c') && mods.contains(KeyModifiers::CONTROL) {
+        inputs.push_event(InputEvent::AppQuit);
+        return;
+    }
+
+    let keycode = match key.code {
+        KeyCode::Char(ch) if ch.is_ascii_uppercase() => {
+            mods |= KeyModifiers::SHIFT;
+            Keycode::from_i32(ch.to_ascii_lowercase() as i32)
+        }
+        KeyCode::Char(ch) if ch.is_ascii() => {
+            mods -= KeyModifiers::SHIFT;
+            Keycode::from_i32(ch as i32)
+        }
+        KeyCode::BackTab => {
+            mods |= KeyModifiers::SHIFT;
+            special_keycode(key.code)
+        }
+        code => special_keycode(code),
+    };
+
+    if let Some(keycode) = keycode {
+        let mod_keys: Vec<Keycode> = [
+            (KeyModifiers::SHIFT, Keycode::LShift),
+            (KeyModifiers::CONTROL, Keycode::LCtrl),
+            (KeyModifiers::ALT, Keycode::LAlt),
+        ]
+        .iter()
+        .filter(|(m, _)| mods.contains(*m))
+        .map(|(_, k)| *k)
+        .collect();
+
+        for mod_key in &mod_keys {
+            inputs.push_event(InputEvent::Press(*mod_key));
+        }
+        inputs.push_event(InputEvent::Press(keycode));
+        inputs.push_event(InputEvent::Release(keycode));
+        for mod_key in mod_keys.iter().rev() {
+            inputs.push_event(InputEvent::Release(*mod_key));
+        }
+    }
+
+    // Typed characters also come through as text while text input is active.
+    if let KeyCode::Char(ch) = key.code {
+        if inputs.is_text_input_active()
+            && !key
+                .modifiers
+                .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+        {
+            inputs.push_event(InputEvent::Text(ch.to_string()));
+        }
+    }
+}
+
+fn handle_event(inputs: &mut InputBuffer, term_size: &mut (u16, u16), event: Event) {
+    match event {
+        Event::Key(key) => handle_key(inputs, key),
+        Event::Resize(w, h) => *term_size = (w, h),
+        Event::Mouse(_) => {}
+    }
+}
+
+/// Run a main loop like [crate::run()], but draw to the terminal instead of a window.
+///
+/// Each TileGrid cell is shown as a terminal cell, using [Symbol::text_fallback] for symbols and
+/// 24-bit color escape codes for the foreground and background colors.  Each terminal cell stands
+/// in for a tile of the first tileset, so the window size given to `update` is the terminal size
+/// measured in those tiles.  Grids drawn with other tilesets still get one terminal cell per grid
+/// cell, anchored at the middle of their views.
+///
+/// Keys pressed in the terminal are turned into [InputEvent]s, with Ctrl+C sent as
+/// [InputEvent::AppQuit].  Typed characters are also sent as [InputEvent::Text] while text input
+/// is active.  Mice and game controllers are not supported.
+///
+/// `settings.title`, `settings.window_size`, `settings.window_mode`, `settings.integer_scale` and
+/// `settings.hot_reload_tilesets` are ignored.  The tile images are still loaded, since TileGrids
+/// need their tilesets for layout.
+pub fn run_terminal<U, Y>(settings: RunSettings<Y>, mut update: U)
+where
+    U: FnMut(&mut InputBuffer, &mut Vec<TileGridLayer<Y>>, &[Tileset<Y>], Size) -> RunControl,
+    Y: Symbol,
+{
+    let _image_context = sdl2::image::init(sdl2::image::InitFlag::PNG).unwrap();
+
+    assert!(!settings.tileset_infos.is_empty());
+    assert!(settings.fps > 0);
+
+    let unit = settings.tileset_infos[0].tile_size;
+
+    assert!(unit.w > 0 && unit.h > 0);
+
+    let mut tilesets = Vec::with_capacity(settings.tileset_infos.len());
+    for tileset_info in settings.tileset_infos {
+        tilesets.push(Tileset::new(tileset_info));
+    }
+
+    let mut out = BufWriter::new(io::stdout());
+    let _guard = TerminalGuard::enter(&mut out).unwrap();
+    let mut term_size = terminal::size().unwrap();
+    let mut layers: Vec<TileGridLayer<Y>> = Vec::new();
+    let mut inputs = InputBuffer::new();
+    let mut frame = Vec::new();
+    let mut shown = Vec::new();
+    let mut active_update = true;
+
+    let frame_time = Duration::new(0, 1_000_000_000u32 / settings.fps);
+    let mut next_frame = Instant::now();
+
+    loop {
+        let animating = layers
+            .iter()
+            .flat_map(|l| &l.grids)
+            .any(|g| g.is_animating());
+        let waiting = !active_update && !inputs.more_inputs();
+        let mut got_event = false;
+
+        // Block for an event if waiting is requested, unless animations need more frames.
+        if waiting && !animating {
+            handle_event(&mut inputs, &mut term_size, event::read().unwrap());
+            got_event = true;
+            next_frame = Instant::now();
+        }
+
+        // Collect events until the next frame is due when updating actively or animating,
+        // otherwise just take the ones that are ready.
+        loop {
+            let timeout = if active_update || animating {
+                next_frame.saturating_duration_since(Instant::now())
+            } else {
+                Duration::new(0, 0)
+            };
+
+            if !event::poll(timeout).unwrap() {
+                break;
+            }
+
+            handle_event(&mut inputs, &mut term_size, event::read().unwrap());
+            got_event = true;
+        }
+
+        if active_update || animating {
+            next_frame = (next_frame + frame_time).max(Instant::now());
+        }
+
+        // Guarantee minimum window dimensions, even if we have to fake it.
+        let window_size = Size {
+            w: (term_size.0 as u32 * unit.w).max(settings.min_window_size.w),
+            h: (term_size.1 as u32 * unit.h).max(settings.min_window_size.h),
+        };
+
+        // Only show the next animation frame if the wait for an event ran out.
+        if !waiting || got_event {
+            match update(&mut inputs, &mut layers, &tilesets[..], window_size) {
+                RunControl::Update => active_update = true,
+                RunControl::WaitForEvent => active_update = false,
+                RunControl::Quit => break,
+            }
+        }
+
+        // There's no canvas to read pixels back from or window to change, so drop any such requests.
+        inputs.take_screenshot_request();
+        inputs.take_window_mode_request();
+        inputs.take_integer_scale_request();
+
+        // Display the grids, starting from the lowest visible layer.
+        let start_layer_draw_from = layers.iter().rposition(|l| !l.draw_behind).unwrap_or(0);
+
+        compose(
+            &layers[start_layer_draw_from..],
+            &tilesets[..],
+            unit,
+            term_size,
+            &mut frame,
+        );
+        present(&mut out, &frame, &mut shown, term_size.0).unwrap();
+
+        for layer in &mut layers {
+            for grid in &mut layer.grids {
+                grid.advance_animations();
+            }
+        }
+
+        // Discard any current input to make way for the next one.
+        inputs.clear_input();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tilegrid::{
+        tests::{font_tileset_info, NoSym},
+        TileGrid,
+    };
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent { code, modifiers }
+    }
+
+    /// Pull all buffered events out of an InputBuffer as short descriptions.
+    fn buffered_events(inputs: &mut InputBuffer) -> Vec<String> {
+        let mut events = Vec::new();
+
+        loop {
+            inputs.prepare_input();
+            events.push(match inputs.get_input() {
+                Some(InputEvent::AppQuit) => "quit".to_string(),
+                Some(InputEvent::Press(keycode)) => format!("+{:?}", keycode),
+                Some(InputEvent::Release(keycode)) => format!("-{:?}", keycode),
+                Some(InputEvent::Text(text)) => format!("text {}", text),
+                Some(_) => panic!("unexpected input event"),
+                None => break,
+            });
+            inputs.clear_input();
+        }
+
+        events
+    }
+
+    fn key_events(inputs: &mut InputBuffer, code: KeyCode, modifiers: KeyModifiers) -> Vec<String> {
+        handle_key(inputs, key(code, modifiers));
+        buffered_events(inputs)
+    }
+
+    #[test]
+    fn keys_are_pressed_and_released_inside_their_modifiers() {
+        let mut inputs = InputBuffer::new();
+        let none = KeyModifiers::NONE;
+
+        assert_eq!(
+            key_events(&mut inputs, KeyCode::Char('a'), none),
+            ["+A", "-A"]
+        );
+        assert_eq!(
+            key_events(&mut inputs, KeyCode::F(5), KeyModifiers::ALT),
+            ["+LAlt", "+F5", "-F5", "-LAlt"]
+        );
+        assert_eq!(
+            key_events(
+                &mut inputs,
+                KeyCode::Char('x'),
+                KeyModifiers::CONTROL | KeyModifiers::ALT
+            ),
+            ["+LCtrl", "+LAlt", "+X", "-X", "-LAlt", "-LCtrl"]
+        );
+        assert_eq!(
+            key_events(&mut inputs, KeyCode::Enter, none),
+            ["+Return", "-Return"]
+        );
+        assert!(key_events(&mut inputs, KeyCode::F(13), none).is_empty());
+    }
+
+    #[test]
+    fn uppercase_letters_and_back_tab_are_shifted() {
+        let mut inputs = InputBuffer::new();
+        let shifted = ["+LShift", "+Q", "-Q", "-LShift"];
+
+        // Some terminals report Shift with uppercase letters and some don't.
+        assert_eq!(
+            key_events(&mut inputs, KeyCode::Char('Q'), KeyModifiers::NONE),
+            shifted
+        );
+        assert_eq!(
+            key_events(&mut inputs, KeyCode::Char('Q'), KeyModifiers::SHIFT),
+            shifted
+        );
+        assert_eq!(
+            key_events(&mut inputs, KeyCode::BackTab, KeyModifiers::NONE),
+            ["+LShift", "+Tab", "-Tab", "-LShift"]
+        );
+    }
+
+    #[test]
+    fn shifted_symbols_arrive_without_shift() {
+        let mut inputs = InputBuffer::new();
+
+        assert_eq!(
+            key_events(&mut inputs, KeyCode::Char('>'), KeyModifiers::SHIFT),
+            ["+Greater", "-Greater"]
+        );
+        assert_eq!(
+            key_events(&mut inputs, KeyCode::Char('?'), KeyModifiers::SHIFT),
+            ["+Question", "-Question"]
+        );
+    }
+
+    #[test]
+    fn ctrl_c_quits() {
+        let mut inputs = InputBuffer::new();
+
+        assert_eq!(
+            key_events(&mut inputs, KeyCode::Char('c'), KeyModifiers::CONTROL),
+            ["quit"]
+        );
+        assert_eq!(
+            key_events(&mut inputs, KeyCode::Char('c'), KeyModifiers::NONE),
+            ["+C", "-C"]
+        );
+    }
+
+    #[test]
+    fn characters_are_typed_while_text_input_is_active() {
+        let mut inputs = InputBuffer::new();
+
+        inputs.start_text_input();
+        assert_eq!(
+            key_events(&mut inputs, KeyCode::Char('a'), KeyModifiers::NONE),
+            ["+A", "-A", "text a"]
+        );
+        assert_eq!(
+            key_events(&mut inputs, KeyCode::Char('A'), KeyModifiers::SHIFT),
+            ["+LShift", "+A", "-A", "-LShift", "text A"]
+        );
+        assert_eq!(
+            key_events(&mut inputs, KeyCode::Char('é'), KeyModifiers::NONE),
+            ["text é"]
+        );
+        assert_eq!(
+            key_events(&mut inputs, KeyCode::Char('s'), KeyModifiers::CONTROL),
+            ["+LCtrl", "+S", "-S", "-LCtrl"]
+        );
+
+        inputs.stop_text_input();
+        assert_eq!(
+            key_events(&mut inputs, KeyCode::Char('a'), KeyModifiers::NONE),
+            ["+A", "-A"]
+        );
+    }
+
+    #[test]
+    fn clip_spans_round_to_cells_within_the_terminal() {
+        assert_eq!(clip_span(16, 24, 8, 10), (2, 5));
+        assert_eq!(clip_span(12, 8, 8, 10), (2, 3));
+        assert_eq!(clip_span(-20, 40, 8, 10), (0, 3));
+        assert_eq!(clip_span(60, 40, 8, 10), (8, 10));
+        assert_eq!(clip_span(100, 40, 8, 10), (13, 10));
+    }
+
+    /// Characters of each row of a composed frame.
+    fn frame_rows(frame: &[TermCell], cols: u16) -> Vec<String> {
+        frame
+            .chunks(cols as usize)
+            .map(|row| row.iter().map(|cell| cell.0).collect())
+            .collect()
+    }
+
+    #[test]
+    fn compose_places_grids_by_their_views() {
+        let tilesets = vec![Tileset::new(font_tileset_info())];
+        let unit = Size { w: 8, h: 14 };
+        let size = (6, 4);
+        let mut frame = Vec::new();
+
+        let mut lower = TileGrid::<NoSym>::new((3, 2).into(), &tilesets, 0);
+        lower.view.pos = (8, 14).into();
+        lower.view.size = (32, 28).into();
+        lower.view.clear_color = Some(Color::BLUE);
+        lower.print((0, 0), "abc");
+        lower.print((0, 1), "d\t");
+
+        // Scrolled a cell left and clipped to one cell, without clearing behind it.
+        let mut upper = TileGrid::<NoSym>::new((2, 1).into(), &tilesets, 0);
+        upper.view.pos = (32, 42).into();
+        upper.view.size = (8, 14).into();
+        upper.view.dx = -8;
+        upper.view.clear_color = None;
+        upper.view.color_mod = Color {
+            r: 127,
+            g: 127,
+            b: 127,
+        };
+        upper.print((0, 0), "yz");
+
+        let layers = vec![
+            TileGridLayer {
+                draw_behind: false,
+                grids: vec![lower],
+            },
+            TileGridLayer {
+                draw_behind: true,
+                grids: vec![upper],
+            },
+        ];
+
+        compose(&layers, &tilesets, unit, size, &mut frame);
+
+        assert_eq!(
+            frame_rows(&frame, size.0),
+            ["      ", " abc  ", " d    ", "    z "]
+        );
+        assert!(frame[6 + 1] == ('a', Color::WHITE, Color::BLACK));
+        // Control characters are blanked out, and the clear color fills the rest of the view.
+        assert!(frame[12 + 2] == (' ', Color::WHITE, Color::BLACK));
+        assert!(frame[6 + 4] == (' ', Color::BLUE, Color::BLUE));
+        assert!(
+            frame[18 + 4]
+                == (
+                    'z',
+                    Color {
+                        r: 127,
+                        g: 127,
+                        b: 127
+                    },
+                    Color::BLACK
+                )
+        );
+        assert!(frame[0] == BLANK);
+    }
+
+    #[test]
+    fn compose_centers_grids_of_other_tile_sizes() {
+        let tilesets = vec![Tileset::new(font_tileset_info())];
+        let unit = Size { w: 8, h: 14 };
+        let size = (8, 3);
+        let mut frame = Vec::new();
+
+        // Tiles twice the size of terminal cells, shown one per cell in the middle of the view.
+        let mut grid = TileGrid::<NoSym>::new((2, 1).into(), &tilesets, 0);
+        grid.view.size = (64, 42).into();
+        grid.view.dx = 16;
+        grid.view.dy = 7;
+        grid.view.zoom = 2;
+        grid.view.clear_color = Some(Color::BLUE);
+        grid.print((0, 0), "ok");
+
+        let layers = vec![TileGridLayer {
+            draw_behind: false,
+            grids: vec![grid],
+        }];
+
+        compose(&layers, &tilesets, unit, size, &mut frame);
+
+        assert_eq!(
+            frame_rows(&frame, size.0),
+            ["        ", "   ok   ", "        "]
+        );
+        assert!(frame[0] == (' ', Color::BLUE, Color::BLUE));
+    }
+}
@@ -1,14 +1,23 @@
 use sdl2::{
-    image::LoadSurface,
+    image::{ImageRWops, LoadSurface, SaveSurface},
     pixels::{Color as Sdl2Color, PixelFormatEnum},
     rect::Rect,
     render::{BlendMode, Texture, TextureCreator, WindowCanvas},
+    rwops::RWops,
     surface::Surface,
     video::WindowContext,
 };
-use std::{collections::HashMap, hash::Hash, path::PathBuf};
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    path::{Path, PathBuf},
+};
 
-use crate::util::{Color, Position, Size};
+use crate::{
+    markup::parse_markup,
+    util::{Color, Position, Size},
+    word_wrap::{char_width, text_width},
+};
 
 const U32_SIZE: usize = std::mem::size_of::<u32>();
 
@@ -20,10 +29,30 @@ pub trait Symbol: Copy + Clone + Eq + PartialEq + Hash {
     fn text_fallback(self) -> char;
 }
 
-/// Data describing a tileset that can be loaded from an image on a file system.
+/// Where the tile image of a tileset is loaded from.
+#[derive(Clone)]
+pub enum TilesetImage {
+    /// Path to an image file on the file system.
+    Path(PathBuf),
+    /// Contents of an image file held in memory, e.g. embedded with `include_bytes!`.
+    Bytes(&'static [u8]),
+}
+
+impl TilesetImage {
+    /// Load the image into a surface.  An [sdl2::image::Sdl2ImageContext] must be active.
+    fn load(&self) -> Result<Surface<'static>, String> {
+        match self {
+            TilesetImage::Path(path) => Surface::from_file(path),
+            TilesetImage::Bytes(bytes) => RWops::from_bytes(bytes)?.load(),
+        }
+    }
+}
+
+/// Data describing a tileset that can be loaded from an image in a file or in memory.
+#[derive(Clone)]
 pub struct TilesetInfo<Y: Symbol> {
-    /// Path to the tile image.
-    pub image_path: PathBuf,
+    /// The tile image.
+    pub image: TilesetImage,
     /// Pixel width and height of tiles in the tileset.
     pub tile_size: Size,
     /// Pixel offset of the top-left tile in the tileset.
@@ -88,23 +117,24 @@ impl<'s, Y: Symbol> Tileset<'s, Y> {
         tile_start: Position,
         tile_gap: Size,
         image_size: Size,
-    ) {
+    ) -> Result<(), String> {
         let tile_span_x = tile_size.w + tile_gap.w;
         let tile_span_y = tile_size.h + tile_gap.h;
 
         for (tile_x, tile_y) in tile_indexes {
-            assert!(
-                tile_x >= 0
-                    && tile_y >= 0
-                    && tile_start.x as u32 + tile_x as u32 * tile_span_x + tile_size.w
-                        <= image_size.w
-                    && tile_start.y as u32 + tile_y as u32 * tile_span_y + tile_size.h
-                        <= image_size.h,
-                "({}, {}) outside of tile image bounds",
-                tile_x,
-                tile_y,
-            );
+            if !(tile_x >= 0
+                && tile_y >= 0
+                && tile_start.x as u32 + tile_x as u32 * tile_span_x + tile_size.w <= image_size.w
+                && tile_start.y as u32 + tile_y as u32 * tile_span_y + tile_size.h <= image_size.h)
+            {
+                return Err(format!(
+                    "({}, {}) outside of tile image bounds",
+                    tile_x, tile_y
+                ));
+            }
         }
+
+        Ok(())
     }
 
     /// Give y positions to TileIndex values that aren't already mapped.
@@ -203,18 +233,23 @@ impl<'s, Y: Symbol> Tileset<'s, Y> {
     /// Panics if no tiles are mapped, the tile image cannot be loaded, or if any entry of the font
     /// map lies outside the tile image bounds.
     pub fn new(tileset_info: TilesetInfo<Y>) -> Self {
-        assert!(
-            !tileset_info.font_map.is_empty() || !tileset_info.symbol_map.is_empty(),
-            "at least one tile must be mapped"
-        );
+        Self::load(tileset_info).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Like [Tileset::new], but returns an error instead of panicking if the tileset can't be
+    /// created, e.g. when the tile image is in the middle of being saved by an image editor.
+    pub fn load(tileset_info: TilesetInfo<Y>) -> Result<Self, String> {
+        if tileset_info.font_map.is_empty() && tileset_info.symbol_map.is_empty() {
+            return Err("at least one tile must be mapped".to_string());
+        }
         assert!(tileset_info.tile_start.x >= 0 && tileset_info.tile_start.y >= 0);
 
         let tile_w = tileset_info.tile_size.w;
         let tile_h = tileset_info.tile_size.h;
-        let image = Surface::from_file(tileset_info.image_path)
-            .unwrap()
-            .convert_format(PixelFormatEnum::ARGB8888)
-            .unwrap();
+        let image = tileset_info
+            .image
+            .load()?
+            .convert_format(PixelFormatEnum::ARGB8888)?;
 
         Self::validate_tile_indexes(
             tileset_info.font_map.values().copied(),
@@ -225,7 +260,7 @@ impl<'s, Y: Symbol> Tileset<'s, Y> {
                 w: image.width(),
                 h: image.height(),
             },
-        );
+        )?;
 
         Self::validate_tile_indexes(
             tileset_info.symbol_map.values().copied(),
@@ -236,7 +271,7 @@ impl<'s, Y: Symbol> Tileset<'s, Y> {
                 w: image.width(),
                 h: image.height(),
             },
-        );
+        )?;
 
         // Create a mapping from TileIndex to y positions.
         let mut tile_index_to_pos: HashMap<TileIndex, i32> = HashMap::new();
@@ -290,11 +325,11 @@ impl<'s, Y: Symbol> Tileset<'s, Y> {
             tileset_info.tile_gap,
         );
 
-        Self {
+        Ok(Self {
             surface,
             tile_size: tileset_info.tile_size,
             cellsym_map,
-        }
+        })
     }
 
     /// Pixel width of each tileset tile.
@@ -501,27 +536,35 @@ impl<Y: Symbol> RawTileGrid<Y> {
         F: Into<Option<Color>> + Copy,
         B: Into<Option<Color>> + Copy,
     {
-        if pos.y >= 0
-            && pos.y < self.size.h as i32
-            && pos.x < self.size.w as i32
-            && pos.x + s.len() as i32 > 0
-        {
-            for (i, c) in s
-                .chars()
-                .skip(-pos.x.min(0) as usize)
-                .take(self.size.w.saturating_sub(pos.x.max(0) as u32) as usize)
-                .filter(|c| draw_space || *c != ' ')
-                .enumerate()
-            {
-                self.put_color_raw(
-                    Position {
-                        x: pos.x + i as i32,
-                        y: pos.y,
-                    },
-                    CellSym::<Y>::Char(c),
-                    fg,
-                    bg,
-                );
+        if pos.y >= 0 && pos.y < self.size.h as i32 {
+            let mut x = pos.x;
+
+            // Place characters by display width, like word wrapping measures them.
+            for c in s.chars() {
+                let width = char_width(c) as i32;
+
+                if x >= self.size.w as i32 {
+                    break;
+                }
+
+                if width > 0 {
+                    if x >= 0 && (draw_space || c != ' ') {
+                        self.put_color_raw(Position { x, y: pos.y }, CellSym::<Y>::Char(c), fg, bg);
+                    }
+
+                    // Blank out the rest of the cells covered by a wide character.
+                    for blank_x in (x + 1)..(x + width).min(self.size.w as i32) {
+                        if blank_x >= 0 {
+                            let blank_pos = Position {
+                                x: blank_x,
+                                y: pos.y,
+                            };
+                            self.put_color_raw(blank_pos, CellSym::<Y>::Char(' '), fg, bg);
+                        }
+                    }
+                }
+
+                x += width;
             }
         }
     }
@@ -677,6 +720,71 @@ impl<Y: Symbol> RawTileGrid<Y> {
     }
 }
 
+/// A character or symbol shown by an [AnimationCell].
+#[derive(Clone, Copy)]
+pub enum AnimationGlyph<Y: Symbol> {
+    Char(char),
+    Sym(Y),
+}
+
+/// A cell drawn over a TileGrid during one frame of a [TileAnimation].  `None` for the glyph or
+/// either color keeps what's drawn in the TileGrid underneath, e.g. to flash just the background.
+#[derive(Clone, Copy)]
+pub struct AnimationCell<Y: Symbol> {
+    pub pos: Position,
+    pub glyph: Option<AnimationGlyph<Y>>,
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+}
+
+impl<Y: Symbol> AnimationCell<Y> {
+    /// Draw this cell over a cell of the TileGrid.
+    fn apply_to(&self, cell: &mut Cell<Y>) {
+        match self.glyph {
+            Some(AnimationGlyph::Char(ch)) => cell.csym = CellSym::Char(ch),
+            Some(AnimationGlyph::Sym(sym)) => cell.csym = CellSym::Sym(sym),
+            None => {}
+        }
+        if let Some(fg) = self.fg {
+            cell.fg = fg;
+        }
+        if let Some(bg) = self.bg {
+            cell.bg = bg;
+        }
+    }
+}
+
+/// A short sequence of frames of cells drawn over a TileGrid without changing its contents,
+/// started with [TileGrid::animate].
+///
+/// [crate::run()] advances animations once per displayed frame, and keeps displaying frames while
+/// any are playing, even if `update` asked to wait for an event.
+pub struct TileAnimation<Y: Symbol> {
+    frames: Vec<Vec<AnimationCell<Y>>>,
+    ticks_per_frame: u32,
+    ticks: u32,
+}
+
+impl<Y: Symbol> TileAnimation<Y> {
+    /// Create an animation that shows each of `frames` in turn for `ticks_per_frame` displayed
+    /// frames each.
+    pub fn new(frames: Vec<Vec<AnimationCell<Y>>>, ticks_per_frame: u32) -> Self {
+        assert!(ticks_per_frame > 0);
+
+        Self {
+            frames,
+            ticks_per_frame,
+            ticks: 0,
+        }
+    }
+
+    fn current_frame(&self) -> Option<&[AnimationCell<Y>]> {
+        self.frames
+            .get((self.ticks / self.ticks_per_frame) as usize)
+            .map(Vec::as_slice)
+    }
+}
+
 /// Where and how a TileGrid should be displayed on screen.
 pub struct TileGridView {
     /// Top-left pixel position of the clipping rectangle in which the TileGrid will be displayed.
@@ -709,6 +817,7 @@ pub struct TileGrid<'b, 'r, Y: Symbol> {
     tileset_index: usize,
     buffer: Option<Surface<'b>>,
     texture: Option<Texture<'r>>,
+    animations: Vec<TileAnimation<Y>>,
     pub view: TileGridView,
 }
 
@@ -731,6 +840,7 @@ impl<'b, 'r, Y: Symbol> TileGrid<'b, 'r, Y> {
             tileset_index,
             buffer: None,
             texture: None,
+            animations: Vec::new(),
             view: TileGridView {
                 pos: Position { x: 0, y: 0 },
                 size: Size { w: 640, h: 480 },
@@ -754,6 +864,56 @@ impl<'b, 'r, Y: Symbol> TileGrid<'b, 'r, Y> {
         self.front.size.h
     }
 
+    /// Get the character and the foreground and background colors of the cell at a given position,
+    /// using [Symbol::text_fallback] for cells holding symbols, as currently shown by any playing
+    /// animations.  Returns `None` if the position lies outside the TileGrid.
+    pub fn get_cell_text<P: Into<Position>>(&self, pos: P) -> Option<(char, Color, Color)> {
+        let pos = pos.into();
+
+        if pos.x >= 0
+            && pos.y >= 0
+            && pos.x < self.front.size.w as i32
+            && pos.y < self.front.size.h as i32
+        {
+            let mut cell = self.front.cells[self.front.index(pos)];
+
+            for animation_cell in self
+                .animations
+                .iter()
+                .filter_map(TileAnimation::current_frame)
+                .flatten()
+                .filter(|c| c.pos == pos)
+            {
+                animation_cell.apply_to(&mut cell);
+            }
+
+            let ch = match cell.csym {
+                CellSym::<Y>::Char(ch) => ch,
+                CellSym::<Y>::Sym(sym) => sym.text_fallback(),
+            };
+
+            Some((ch, cell.fg, cell.bg))
+        } else {
+            None
+        }
+    }
+
+    /// Get the contents of the TileGrid as lines of text, using [Symbol::text_fallback] for cells
+    /// holding symbols.
+    pub fn to_text(&self) -> String {
+        let mut text =
+            String::with_capacity((self.front.size.w as usize + 1) * self.front.size.h as usize);
+
+        for y in 0..self.front.size.h as i32 {
+            for x in 0..self.front.size.w as i32 {
+                text.push(self.get_cell_text((x, y)).unwrap().0);
+            }
+            text.push('\n');
+        }
+
+        text
+    }
+
     /// Resize the TileGrid to the given grid dimensions, skipping if the dimensions are identical.
     ///
     /// If a resize occurs, the grid contents will need to be redrawn, and internal flags will be
@@ -775,11 +935,97 @@ impl<'b, 'r, Y: Symbol> TileGrid<'b, 'r, Y> {
         self.needs_upload = true;
     }
 
-    /// Make the TileGrid recreate its texture in the next call to [TileGrid::display].
+    /// Make the TileGrid render its contents afresh and recreate its texture in the next call to
+    /// [TileGrid::display].
     pub fn flag_texture_recreate(&mut self) {
+        self.force_render = true;
         self.texture = None;
     }
 
+    /// Start playing an animation over the TileGrid; see [TileAnimation].
+    pub fn animate(&mut self, animation: TileAnimation<Y>) {
+        self.animations.push(animation);
+        self.needs_render = true;
+    }
+
+    /// Returns true if any animations are playing over the TileGrid.
+    pub fn is_animating(&self) -> bool {
+        !self.animations.is_empty()
+    }
+
+    /// Stop all animations playing over the TileGrid.
+    pub fn clear_animations(&mut self) {
+        if !self.animations.is_empty() {
+            self.animations.clear();
+            self.needs_render = true;
+        }
+    }
+
+    /// Move animations playing over the TileGrid by the given number of cells, e.g. to keep them over
+    /// what they were started over when the contents of the TileGrid scroll.
+    pub fn shift_animations<P: Into<Position>>(&mut self, offset: P) {
+        let offset = offset.into();
+
+        if (offset.x != 0 || offset.y != 0) && !self.animations.is_empty() {
+            for animation in &mut self.animations {
+                for cell in animation.frames.iter_mut().flatten() {
+                    cell.pos.x += offset.x;
+                    cell.pos.y += offset.y;
+                }
+            }
+            self.needs_render = true;
+        }
+    }
+
+    /// Advance animations playing over the TileGrid by one displayed frame, dropping any that have
+    /// finished.  Main loops call this after displaying each frame.
+    pub fn advance_animations(&mut self) {
+        if !self.animations.is_empty() {
+            for animation in &mut self.animations {
+                animation.ticks += 1;
+            }
+            self.animations.retain(|a| a.current_frame().is_some());
+            self.needs_render = true;
+        }
+    }
+
+    /// Draw the current frames of animations over the front buffer, returning the indexes and
+    /// original contents of the cells they cover.
+    fn cover_with_animations(&mut self) -> Vec<(usize, Cell<Y>)> {
+        let mut covered = Vec::new();
+
+        for animation_cell in self
+            .animations
+            .iter()
+            .filter_map(TileAnimation::current_frame)
+            .flatten()
+        {
+            let pos = animation_cell.pos;
+
+            if pos.x >= 0
+                && pos.y >= 0
+                && pos.x < self.front.size.w as i32
+                && pos.y < self.front.size.h as i32
+            {
+                let index = self.front.index(pos);
+
+                covered.push((index, self.front.cells[index]));
+                animation_cell.apply_to(&mut self.front.cells[index]);
+            }
+        }
+
+        covered
+    }
+
+    /// Put back the cells covered by [TileGrid::cover_with_animations] so the grid contents are
+    /// unaffected by animations, and make sure they're rendered again once the animations move on.
+    fn uncover_animations(&mut self, covered: Vec<(usize, Cell<Y>)>) {
+        for (index, cell) in covered.into_iter().rev() {
+            self.front.cells[index] = cell;
+            self.needs_render = true;
+        }
+    }
+
     /// Get the tileset index for the Tileset assigned to the TileGrid.
     pub fn tileset(&self) -> usize {
         self.tileset_index
@@ -828,6 +1074,46 @@ impl<'b, 'r, Y: Symbol> TileGrid<'b, 'r, Y> {
         }
     }
 
+    /// Convert a pixel position on the screen into the position of the cell displayed there,
+    /// according to the TileGrid's view.  Returns `None` if the pixel position lies outside the
+    /// clipping rectangle of the view, outside the grid itself, or if the TileGrid isn't visible.
+    pub fn screen_to_cell<P: Into<Position>>(
+        &self,
+        tilesets: &[Tileset<Y>],
+        screen_pos: P,
+    ) -> Option<Position> {
+        let Position { x, y } = screen_pos.into();
+
+        if !self.view.visible
+            || self.view.zoom == 0
+            || x < self.view.pos.x
+            || y < self.view.pos.y
+            || x >= self.view.pos.x + self.view.size.w as i32
+            || y >= self.view.pos.y + self.view.size.h as i32
+        {
+            return None;
+        }
+
+        let tileset = &tilesets[self.tileset_index];
+        let cell_px_w = (tileset.tile_width() * self.view.zoom) as i32;
+        let cell_px_h = (tileset.tile_height() * self.view.zoom) as i32;
+        let grid_px_x = x - self.view.pos.x - self.view.dx;
+        let grid_px_y = y - self.view.pos.y - self.view.dy;
+
+        if grid_px_x >= 0
+            && grid_px_y >= 0
+            && grid_px_x < self.front.size.w as i32 * cell_px_w
+            && grid_px_y < self.front.size.h as i32 * cell_px_h
+        {
+            Some(Position {
+                x: grid_px_x / cell_px_w,
+                y: grid_px_y / cell_px_h,
+            })
+        } else {
+            None
+        }
+    }
+
     /// Set internal drawing offset hint to take advantage of wrapped offset rendering to reduce
     /// time spent rendering later on.
     ///
@@ -937,6 +1223,10 @@ impl<'b, 'r, Y: Symbol> TileGrid<'b, 'r, Y> {
     /// foreground and/or background colors.  If the string goes past the right edge of the
     /// TileGrid it will be truncated.  If `skip_space` is true space characters will overwrite
     /// cells instead of skipping them and preserving their contents.
+    ///
+    /// Characters take up cells by display width like [crate::word_wrap] measures them: zero-width
+    /// characters such as combining marks are skipped, and wide characters fill their first cell
+    /// and blank out the second.
     pub fn print_color<P, F, B>(&mut self, pos: P, s: &str, draw_space: bool, fg: F, bg: B)
     where
         P: Into<Position>,
@@ -947,6 +1237,27 @@ impl<'b, 'r, Y: Symbol> TileGrid<'b, 'r, Y> {
         self.needs_render = true;
     }
 
+    /// Like [TileGrid::print_color], but the string may contain color markup; see
+    /// [crate::parse_markup].  Text outside of color tags is printed with `fg`.
+    pub fn print_markup<P, F, B>(&mut self, pos: P, s: &str, draw_space: bool, fg: F, bg: B)
+    where
+        P: Into<Position>,
+        F: Into<Option<Color>> + Copy,
+        B: Into<Option<Color>> + Copy,
+    {
+        let mut pos = pos.into();
+
+        for (text, color) in parse_markup(s) {
+            match color {
+                Some(color) => self.front.print_color(pos, text, draw_space, color, bg),
+                None => self.front.print_color(pos, text, draw_space, fg, bg),
+            }
+            pos.x += text_width(text) as i32;
+        }
+
+        self.needs_render = true;
+    }
+
     /// Draw a box on the TileGrid with the given size and position.  Any part of the box that
     /// falls outside of the TileGrid will be clipped off.
     pub fn draw_box<P, S, F, B>(&mut self, pos: P, size: S, fg: F, bg: B)
@@ -1082,7 +1393,9 @@ impl<'b, 'r, Y: Symbol> TileGrid<'b, 'r, Y> {
             self.force_render = true;
         }
 
-        // Render the drawn grid contents to the buffer.
+        // Render the drawn grid contents to the buffer, with any animations drawn over them.
+        let covered = self.cover_with_animations();
+
         if self.needs_render || self.force_render {
             if self.render(tileset, self.force_render) {
                 self.needs_upload = true;
@@ -1091,6 +1404,8 @@ impl<'b, 'r, Y: Symbol> TileGrid<'b, 'r, Y> {
             self.needs_render = false;
         }
 
+        self.uncover_animations(covered);
+
         // The buffer is guaranteed to exist here; make sure the texture exists too.
         let buffer = self.buffer.as_ref().unwrap();
         let texture = match &mut self.texture {
@@ -1249,3 +1564,242 @@ pub struct TileGridLayer<'b, 'r, Y: Symbol> {
     /// TileGrids to be drawn to, rendered and displayed as part of the layer.
     pub grids: Vec<TileGrid<'b, 'r, Y>>,
 }
+
+/// Dump the visible TileGrids of a stack of layers as plain text, starting from the lowest visible
+/// layer like [crate::run()] displays them.  Each grid is preceded by a line naming its layer,
+/// index and size.  No window is needed, so this works with [crate::run_headless] too.
+pub fn layers_to_text<Y: Symbol>(layers: &[TileGridLayer<Y>]) -> String {
+    let start_layer = layers.iter().rposition(|l| !l.draw_behind).unwrap_or(0);
+    let mut text = String::new();
+
+    for (l, layer) in layers.iter().enumerate().skip(start_layer) {
+        for (g, grid) in layer.grids.iter().enumerate() {
+            if grid.view.visible {
+                text.push_str(&format!(
+                    "--- layer {} grid {} ({}x{}) ---\n",
+                    l,
+                    g,
+                    grid.width(),
+                    grid.height(),
+                ));
+                text.push_str(&grid.to_text());
+            }
+        }
+    }
+
+    text
+}
+
+/// Save the contents of the canvas to a PNG image file.  Call this after displaying TileGrids but
+/// before presenting the canvas.
+pub fn save_screenshot(canvas: &WindowCanvas, path: &Path) -> Result<(), String> {
+    let (w, h) = canvas.output_size()?;
+    let format = PixelFormatEnum::ARGB8888;
+    let mut pixels = canvas.read_pixels(None, format)?;
+    let surface = Surface::from_data(
+        &mut pixels,
+        w,
+        h,
+        w * format.byte_size_per_pixel() as u32,
+        format,
+    )?;
+
+    surface.save(path)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Symbol type for tests, which only draw characters.
+    #[derive(Clone, Copy, Eq, Hash, PartialEq)]
+    pub(crate) struct NoSym;
+
+    impl Symbol for NoSym {
+        fn text_fallback(self) -> char {
+            '?'
+        }
+    }
+
+    /// Tileset for tests using the 8x14 font from the game assets.
+    pub(crate) fn font_tileset_info() -> TilesetInfo<NoSym> {
+        TilesetInfo {
+            image: TilesetImage::Bytes(include_bytes!("../../assets/gohufont-8x14.png")),
+            tile_size: (8, 14).into(),
+            tile_start: (0, 0).into(),
+            tile_gap: (0, 0).into(),
+            font_map: TilesetInfo::<NoSym>::map_code_page_437(),
+            symbol_map: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn layers_to_text_dumps_visible_grids_from_the_lowest_shown_layer() {
+        let tilesets = vec![Tileset::new(font_tileset_info())];
+        let grid = |size: (u32, u32), lines: &[&str]| {
+            let mut grid = TileGrid::new(size.into(), &tilesets, 0);
+            for (y, line) in lines.iter().enumerate() {
+                grid.print((0, y as i32), line);
+            }
+            grid
+        };
+        let mut hidden = grid((3, 1), &["no"]);
+        hidden.view.visible = false;
+        let mut layers = vec![
+            TileGridLayer {
+                draw_behind: false,
+                grids: vec![grid((4, 2), &["ab", "cde"])],
+            },
+            TileGridLayer {
+                draw_behind: true,
+                grids: vec![hidden, grid((2, 1), &["xy"])],
+            },
+        ];
+
+        assert_eq!(
+            layers_to_text(&layers),
+            "--- layer 0 grid 0 (4x2) ---\nab  \ncde \n--- layer 1 grid 1 (2x1) ---\nxy\n",
+        );
+
+        // Layers that aren't drawn behind hide everything below them.
+        layers.push(TileGridLayer {
+            draw_behind: false,
+            grids: vec![grid((1, 1), &["z"])],
+        });
+
+        assert_eq!(layers_to_text(&layers), "--- layer 2 grid 0 (1x1) ---\nz\n");
+    }
+
+    #[test]
+    fn printing_places_characters_by_display_width() {
+        let tilesets = vec![Tileset::new(font_tileset_info())];
+        let mut grid = TileGrid::<NoSym>::new((6, 1).into(), &tilesets, 0);
+        let fg = Color::WHITE;
+        let bg = Color::BLACK;
+
+        // Combining marks are skipped and wide characters blank out their second cell.
+        grid.print((0, 0), "xxxxxx");
+        grid.print((0, 0), "e\u{301}\u{6f22}b");
+        assert_eq!(grid.to_text(), "e\u{6f22} bxx\n");
+
+        // Skipped spaces still take up their cells.
+        grid.print((0, 0), "xxxxxx");
+        grid.print_color((0, 0), "a b \u{6f22}", false, fg, bg);
+        assert_eq!(grid.to_text(), "axbx\u{6f22} \n");
+
+        // Wide characters clipped at either edge.
+        grid.print((0, 0), "xxxxxx");
+        grid.print((-1, 0), "\u{6f22}a");
+        grid.print((5, 0), "\u{5b57}");
+        assert_eq!(grid.to_text(), " axxx\u{5b57}\n");
+
+        // Markup advances past wide characters by their width.
+        grid.print((0, 0), "xxxxxx");
+        grid.print_markup((0, 0), "{#ff0000}\u{6f22}{/}y{{", true, fg, bg);
+        assert_eq!(grid.to_text(), "\u{6f22} y{xx\n");
+    }
+
+    fn anim_cell(x: i32, ch: Option<char>, bg: Option<Color>) -> AnimationCell<NoSym> {
+        AnimationCell {
+            pos: Position { x, y: 0 },
+            glyph: ch.map(AnimationGlyph::Char),
+            fg: None,
+            bg,
+        }
+    }
+
+    #[test]
+    fn animations_play_each_frame_for_their_ticks_then_finish() {
+        let tilesets = vec![Tileset::new(font_tileset_info())];
+        let mut grid = TileGrid::<NoSym>::new((4, 1).into(), &tilesets, 0);
+
+        grid.print((0, 0), "abcd");
+        grid.animate(TileAnimation::new(
+            vec![
+                vec![anim_cell(1, Some('X'), None)],
+                vec![anim_cell(2, Some('Y'), Some(Color::RED))],
+            ],
+            2,
+        ));
+        // Later animations are drawn over earlier ones, and can keep the glyph underneath.
+        grid.animate(TileAnimation::new(
+            vec![vec![anim_cell(1, None, Some(Color::BLUE))]],
+            1,
+        ));
+
+        let mut shown = Vec::new();
+        while grid.is_animating() {
+            shown.push(grid.to_text());
+            grid.advance_animations();
+        }
+
+        assert_eq!(shown, ["aXcd\n", "aXcd\n", "abYd\n", "abYd\n"]);
+        assert_eq!(grid.to_text(), "abcd\n");
+        assert!(grid.get_cell_text((2, 0)).unwrap().2 == Color::BLACK);
+    }
+
+    #[test]
+    fn animations_overlay_cells_without_changing_them() {
+        let tilesets = vec![Tileset::new(font_tileset_info())];
+        let mut grid = TileGrid::<NoSym>::new((4, 1).into(), &tilesets, 0);
+
+        grid.print((0, 0), "abcd");
+        grid.animate(TileAnimation::new(
+            vec![vec![
+                anim_cell(-1, Some('!'), None),
+                anim_cell(0, None, Some(Color::RED)),
+                anim_cell(3, Some('Z'), None),
+                anim_cell(4, Some('!'), None),
+            ]],
+            1,
+        ));
+
+        assert!(grid.get_cell_text((0, 0)).unwrap() == ('a', Color::WHITE, Color::RED));
+        assert!(grid.get_cell_text((3, 0)).unwrap() == ('Z', Color::WHITE, Color::BLACK));
+        assert_eq!(grid.to_text(), "abcZ\n");
+
+        // Displaying draws animations into the grid only while rendering it.
+        let covered = grid.cover_with_animations();
+        assert_eq!(covered.len(), 2);
+        assert!(grid.front.cells[3].csym == CellSym::Char('Z'));
+        grid.needs_render = false;
+        grid.uncover_animations(covered);
+        assert!(grid.needs_render);
+        assert!(grid.front.cells[3].csym == CellSym::Char('d'));
+        assert!(grid.front.cells[0].bg == Color::BLACK);
+
+        // Drawing under an animation shows once it's gone.
+        grid.print((3, 0), "e");
+        assert_eq!(grid.to_text(), "abcZ\n");
+        grid.clear_animations();
+        assert!(!grid.is_animating());
+        assert_eq!(grid.to_text(), "abce\n");
+    }
+
+    #[test]
+    fn shifted_animations_follow_scrolled_contents() {
+        let tilesets = vec![Tileset::new(font_tileset_info())];
+        let mut grid = TileGrid::<NoSym>::new((4, 1).into(), &tilesets, 0);
+
+        grid.print((0, 0), "abcd");
+        grid.animate(TileAnimation::new(
+            vec![
+                vec![anim_cell(1, Some('X'), None)],
+                vec![anim_cell(2, Some('Y'), None)],
+            ],
+            1,
+        ));
+
+        grid.shift_animations((1, 0));
+        assert_eq!(grid.to_text(), "abXd\n");
+        grid.advance_animations();
+        assert_eq!(grid.to_text(), "abcY\n");
+
+        // Animations shifted off the grid aren't shown, but keep playing.
+        grid.shift_animations((2, 0));
+        assert_eq!(grid.to_text(), "abcd\n");
+        assert!(grid.is_animating());
+        grid.shift_animations((-3, 0));
+        assert_eq!(grid.to_text(), "abYd\n");
+    }
+}
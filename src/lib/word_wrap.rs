@@ -1,8 +1,40 @@
-/// Word wrap input string into lines with at most `max_length` characters.
+use unicode_width::UnicodeWidthChar;
+
+use crate::{
+    markup::{color_tag, parse_markup},
+    util::Color,
+};
+
+/// Display width of a character in columns, counting control characters as zero.
+pub(crate) fn char_width(ch: char) -> usize {
+    UnicodeWidthChar::width(ch).unwrap_or(0)
+}
+
+/// Display width of a string in columns, as measured by [word_wrap].
+pub fn text_width(s: &str) -> usize {
+    s.chars().map(char_width).sum()
+}
+
+/// Word wrap input string into lines that are at most `max_length` columns wide.
+///
+/// Leading and trailing whitespace is preserved.  Lines are measured by display width, so wide
+/// characters (e.g. CJK) take up two columns and combining characters take up none.  Words break
+/// after hyphens, and words that are too wide to fit on a line by themselves are split.
 ///
-/// Leading and trailing whitespace is preserved.  Accounts for multi-byte UTF-8 encoded
-/// characters, but not combining characters or characters whose width != 1.
+/// A single character that is wider than `max_length` gets a line to itself, so with a `max_length`
+/// of 1, lines holding a wide character are 2 columns wide.  This is the only case where a line can
+/// be wider than `max_length`.
 pub fn word_wrap(input: &str, max_length: usize) -> impl Iterator<Item = &str> {
+    word_wrap_with(input, max_length, &['-'])
+}
+
+/// Like [word_wrap], but words break after any of the characters in `break_after` instead of just
+/// hyphens, e.g. `&['-', '/', '.', '?', '&']` to break long URLs and paths at sensible places.
+pub fn word_wrap_with<'a>(
+    input: &'a str,
+    max_length: usize,
+    break_after: &'a [char],
+) -> impl Iterator<Item = &'a str> + 'a {
     assert!(max_length > 0);
 
     input.lines().flat_map(move |line| {
@@ -12,47 +44,52 @@ pub fn word_wrap(input: &str, max_length: usize) -> impl Iterator<Item = &str> {
             .scan(None, move |state, (pos, ch)| {
                 // Break into words and single spaces.
                 if let Some(ch) = ch {
-                    if let Some((in_word, start_pos, char_count, last_char)) = state {
-                        if *char_count >= max_length || *last_char == '-' || ch.is_whitespace() {
-                            // Line-length or hyphen-divided word, or mid-line whitespace.
+                    let ch_width = char_width(ch);
+
+                    if let Some((in_word, start_pos, width, last_char)) = state {
+                        if (*width > 0 && *width + ch_width > max_length)
+                            || break_after.contains(last_char)
+                            || ch.is_whitespace()
+                        {
+                            // Line-length or break-character-divided word, or mid-line whitespace.
                             let was_word = *in_word;
                             let last_start_pos = *start_pos;
-                            let last_char_count = *char_count;
+                            let last_width = *width;
                             *in_word = !ch.is_whitespace();
                             *start_pos = pos;
-                            *char_count = 1;
+                            *width = ch_width;
                             *last_char = ch;
-                            Some(Some((last_start_pos, pos, last_char_count, was_word)))
+                            Some(Some((last_start_pos, pos, last_width, was_word)))
                         } else if *in_word {
                             // Word continuation.
-                            *char_count += 1;
+                            *width += ch_width;
                             *last_char = ch;
                             Some(None)
                         } else {
                             // Entering a word after whitespace.
                             let was_word = *in_word;
                             let last_start_pos = *start_pos;
-                            let last_char_count = *char_count;
+                            let last_width = *width;
                             *in_word = true;
                             *start_pos = pos;
-                            *char_count = 1;
+                            *width = ch_width;
                             *last_char = ch;
-                            Some(Some((last_start_pos, pos, last_char_count, was_word)))
+                            Some(Some((last_start_pos, pos, last_width, was_word)))
                         }
                     } else {
                         // Start of the line.
                         let in_word = !ch.is_whitespace();
                         let start_pos = pos;
-                        let char_count = 1;
+                        let width = ch_width;
                         let last_char = ch;
-                        *state = Some((in_word, start_pos, char_count, last_char));
+                        *state = Some((in_word, start_pos, width, last_char));
                         Some(None)
                     }
                 } else {
                     // End of the line.
-                    if let Some((in_word, start_pos, char_count, _)) = state {
+                    if let Some((in_word, start_pos, width, _)) = state {
                         // Finish the final word or whitespace.
-                        Some(Some((*start_pos, pos, *char_count, *in_word)))
+                        Some(Some((*start_pos, pos, *width, *in_word)))
                     } else {
                         // Empty line.
                         Some(Some((pos, pos, 0, false)))
@@ -62,14 +99,14 @@ pub fn word_wrap(input: &str, max_length: usize) -> impl Iterator<Item = &str> {
             .filter(Option::is_some)
             .chain(Some(None)) // word sentinel
             .scan(None, move |state, word_data| {
-                if let Some((word_start, word_end, word_char_count, is_word)) = word_data {
-                    if let Some((line_start, line_end, final_end, line_char_count)) = state {
+                if let Some((word_start, word_end, word_width, is_word)) = word_data {
+                    if let Some((line_start, line_end, final_end, line_width)) = state {
                         if is_word {
-                            if *line_char_count + word_char_count <= max_length {
+                            if *line_width + word_width <= max_length {
                                 // Word fits on line, so include it.
                                 *line_end = word_end;
                                 *final_end = word_end;
-                                *line_char_count += word_char_count;
+                                *line_width += word_width;
                                 Some(None)
                             } else {
                                 // Word exceeds line, so start a new line with it instead.
@@ -78,15 +115,15 @@ pub fn word_wrap(input: &str, max_length: usize) -> impl Iterator<Item = &str> {
                                 *line_start = word_start;
                                 *line_end = word_end;
                                 *final_end = word_end;
-                                *line_char_count = word_char_count;
+                                *line_width = word_width;
                                 Some(Some((last_line_start, last_line_end)))
                             }
                         } else {
-                            if *line_char_count + word_char_count <= max_length {
+                            if *line_width + word_width <= max_length {
                                 // Whitespace fits on line, so include it when finishing words.
                                 *final_end = word_end;
                             }
-                            *line_char_count += word_char_count;
+                            *line_width += word_width;
                             Some(None)
                         }
                     } else {
@@ -94,8 +131,8 @@ pub fn word_wrap(input: &str, max_length: usize) -> impl Iterator<Item = &str> {
                         let line_start = word_start;
                         let line_end = if is_word { word_end } else { word_start };
                         let final_end = word_end;
-                        let line_char_count = word_char_count;
-                        *state = Some((line_start, line_end, final_end, line_char_count));
+                        let line_width = word_width;
+                        *state = Some((line_start, line_end, final_end, line_width));
                         Some(None)
                     }
                 } else {
@@ -113,3 +150,143 @@ pub fn word_wrap(input: &str, max_length: usize) -> impl Iterator<Item = &str> {
             .map(move |(start, end)| &line[start..end])
     })
 }
+
+/// Word wrap color markup into lines that are at most `max_length` columns wide, as described by
+/// [word_wrap].  See [crate::parse_markup] for the markup syntax.
+///
+/// Color tags don't count towards line lengths.  Each line starts with a tag for the color that's
+/// in effect where it begins, so lines can be printed separately without losing their colors.
+pub fn word_wrap_markup(input: &str, max_length: usize) -> impl Iterator<Item = String> {
+    // Where each visible character starts in the stripped text, where it's found in the markup and
+    // the color it's shown in.
+    let mut sources: Vec<(usize, usize, usize, Option<Color>)> = Vec::new();
+    let mut stripped = String::with_capacity(input.len());
+
+    for (text, color) in parse_markup(input) {
+        let offset = text.as_ptr() as usize - input.as_ptr() as usize;
+
+        if text == "{" && input[offset..].starts_with("{{") {
+            sources.push((stripped.len(), offset, offset + 2, color));
+        } else {
+            for (pos, ch) in text.char_indices() {
+                let start = offset + pos;
+                sources.push((stripped.len() + pos, start, start + ch.len_utf8(), color));
+            }
+        }
+
+        stripped.push_str(text);
+    }
+
+    let lines: Vec<String> = word_wrap(&stripped, max_length)
+        .map(|line| {
+            if line.is_empty() {
+                return String::new();
+            }
+
+            let start = line.as_ptr() as usize - stripped.as_ptr() as usize;
+            let end = start + line.len();
+            let first = sources.partition_point(|s| s.0 < start);
+            let last = sources.partition_point(|s| s.0 < end) - 1;
+            let mut wrapped = sources[first].3.map_or_else(String::new, color_tag);
+
+            wrapped.push_str(&input[sources[first].1..sources[last].2]);
+            wrapped
+        })
+        .collect();
+
+    lines.into_iter()
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{seq::SliceRandom, Rng, SeedableRng};
+    use rand_xoshiro::Xoshiro128PlusPlus;
+
+    use super::*;
+    use crate::markup::strip_markup;
+
+    /// Pieces of text to test with, including wide characters, combining marks and long unbroken
+    /// words and URLs.
+    const PIECES: &[&str] = &[
+        " ",
+        "  ",
+        "\n",
+        "a",
+        "word",
+        "hyphen-ated",
+        "-",
+        "\u{e9}",
+        "e\u{301}",
+        "\u{6f22}\u{5b57}",
+        "\u{304b}\u{306a}",
+        "https://example.com/path/to?query=1&more=2",
+        "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+    ];
+
+    const TAGS: &[&str] = &["{#ff4040}", "{/}", "{{"];
+
+    const URL_BREAKS: &[char] = &['-', '/', '.', '?', '&'];
+
+    fn random_text(rng: &mut Xoshiro128PlusPlus, pieces: &[&str]) -> String {
+        let len = rng.gen_range(0..30);
+
+        (0..len).map(|_| *pieces.choose(rng).unwrap()).collect()
+    }
+
+    fn without_whitespace(s: &str) -> String {
+        s.chars().filter(|ch| !ch.is_whitespace()).collect()
+    }
+
+    /// Lines may only be wider than `max_length` if they hold a single wide character.
+    fn fits(line: &str, max_length: usize) -> bool {
+        text_width(line) <= max_length || line.chars().filter(|&ch| char_width(ch) > 0).count() == 1
+    }
+
+    #[test]
+    fn lines_never_exceed_max_length() {
+        let mut rng = Xoshiro128PlusPlus::seed_from_u64(0);
+        let markup_pieces: Vec<&str> = PIECES.iter().chain(TAGS).copied().collect();
+
+        for _ in 0..5000 {
+            let text = random_text(&mut rng, PIECES);
+            let markup = random_text(&mut rng, &markup_pieces);
+            let max_length = rng.gen_range(1..24);
+
+            for line in word_wrap(&text, max_length) {
+                assert!(fits(line, max_length), "{:?} in {:?}", line, text);
+            }
+            for line in word_wrap_with(&text, max_length, URL_BREAKS) {
+                assert!(fits(line, max_length), "{:?} in {:?}", line, text);
+            }
+            for line in word_wrap_markup(&markup, max_length) {
+                let stripped = strip_markup(&line);
+                assert!(fits(&stripped, max_length), "{:?} in {:?}", line, markup);
+            }
+        }
+    }
+
+    #[test]
+    fn wrapping_keeps_all_words() {
+        let mut rng = Xoshiro128PlusPlus::seed_from_u64(1);
+
+        for _ in 0..5000 {
+            let text = random_text(&mut rng, PIECES);
+            let max_length = rng.gen_range(1..24);
+            let wrapped: String = word_wrap_with(&text, max_length, URL_BREAKS).collect();
+
+            assert_eq!(without_whitespace(&wrapped), without_whitespace(&text));
+        }
+    }
+
+    #[test]
+    fn wide_characters_get_their_own_lines_when_too_wide() {
+        assert_eq!(
+            word_wrap("\u{6f22}\u{5b57}a b", 1).collect::<Vec<_>>(),
+            ["\u{6f22}", "\u{5b57}", "a", "b"]
+        );
+        assert_eq!(
+            word_wrap("a\u{6f22}\u{301}b", 2).collect::<Vec<_>>(),
+            ["a", "\u{6f22}\u{301}", "b"]
+        );
+    }
+}
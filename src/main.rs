@@ -2,6 +2,7 @@ mod bitgrid;
 mod chunked;
 mod components;
 mod damage;
+mod effects;
 mod experience;
 mod gamekey;
 mod gamesym;
@@ -16,6 +17,7 @@ mod monster;
 mod player;
 mod render;
 mod saveload;
+mod screenshot;
 mod spawn;
 mod ui;
 mod vision;
@@ -26,7 +28,9 @@ use std::{collections::HashMap, path::PathBuf};
 
 use crate::{
     chunked::Camera,
+    effects::Effects,
     experience::Difficulty,
+    gamekey::KeyBindings,
     gamesym::GameSym,
     item::PickUpHint,
     map::Map,
@@ -35,9 +39,10 @@ use crate::{
     modes::{title::TitleMode, ModeStack},
     monster::MonsterTurns,
     player::{PlayerAlive, PlayerId},
-    ui::Options,
+    saveload::SaveSlot,
+    vision::LightMap,
 };
-use ruggrogue::{RunSettings, TilesetInfo};
+use ruggrogue::{RunSettings, TilesetImage, TilesetInfo};
 
 #[derive(Unique, Deserialize, Serialize)]
 pub struct GameSeed(u64);
@@ -56,19 +61,59 @@ extern "C" {
     pub fn ruggrogue_sync_idbfs();
 }
 
+/// A tile image under the `assets` directory.  It's built into the binary, unless tilesets are being
+/// hot reloaded, in which case it's read from the file so that changes to it can be seen.
+macro_rules! asset_image {
+    ($hot_reload:expr, $path:literal) => {
+        if $hot_reload {
+            TilesetImage::Path(PathBuf::from(concat!("assets/", $path)))
+        } else {
+            TilesetImage::Bytes(include_bytes!(concat!("../assets/", $path)))
+        }
+    };
+}
+
 fn main() {
     let world = World::new();
     let game_seed = std::env::args()
-        .nth(1)
-        .and_then(|arg| arg.as_str().parse().ok())
+        .skip(1)
+        .find_map(|arg| arg.as_str().parse().ok())
         .unwrap_or_else(rand::random);
 
-    world.add_unique(Options {
-        tileset: 2,
-        font: 0,
-        map_zoom: 1,
-        text_zoom: 1,
-    });
+    // Set this environment variable to see changes to tile images without restarting.
+    let hot_reload = std::env::var_os("RUGGROGUE_HOT_RELOAD").is_some();
+
+    let tileset_infos = vec![
+        TilesetInfo::<GameSym> {
+            image: asset_image!(hot_reload, "gohufont-8x14.png"),
+            tile_size: (8, 14).into(),
+            tile_start: (0, 0).into(),
+            tile_gap: (0, 0).into(),
+            font_map: TilesetInfo::<GameSym>::map_code_page_437(),
+            symbol_map: HashMap::new(),
+        },
+        TilesetInfo::<GameSym> {
+            image: asset_image!(hot_reload, "terminal-8x8.png"),
+            tile_size: (8, 8).into(),
+            tile_start: (0, 0).into(),
+            tile_gap: (0, 0).into(),
+            font_map: TilesetInfo::<GameSym>::map_code_page_437(),
+            symbol_map: HashMap::new(),
+        },
+        gamesym::urizen_tileset_info(asset_image!(
+            hot_reload,
+            "urizen/urizen-onebit-tileset-mono.png"
+        )),
+    ];
+
+    saveload::migrate_legacy_save_file();
+
+    let options = saveload::load_options(&tileset_infos);
+    let (window_mode, integer_scale) = (options.window_mode, options.integer_scale);
+
+    world.add_unique(options);
+    world.add_unique(KeyBindings::load());
+    world.add_unique(SaveSlot(String::new()));
     world.add_unique(GameSeed(game_seed));
     world.add_unique(TurnCount(0));
     world.add_unique(Wins(0));
@@ -78,6 +123,8 @@ fn main() {
     world.add_unique(MenuMemory::new());
     world.add_unique(Messages::new(100));
     world.add_unique(Map::new(80, 50));
+    world.add_unique(LightMap::new());
+    world.add_unique(Effects::new());
     world.add_unique(PickUpHint(true));
     world.add_unique(PlayerId(world.run(spawn::spawn_player)));
     world.add_unique(PlayerAlive(true));
@@ -89,28 +136,22 @@ fn main() {
         title: "RuggRogue".into(),
         window_size: (896, 560).into(),
         min_window_size: (640, 192).into(),
+        window_mode,
+        integer_scale,
         fps: 30,
-        tileset_infos: vec![
-            TilesetInfo::<GameSym> {
-                image_path: PathBuf::from("assets/gohufont-8x14.png"),
-                tile_size: (8, 14).into(),
-                tile_start: (0, 0).into(),
-                tile_gap: (0, 0).into(),
-                font_map: TilesetInfo::<GameSym>::map_code_page_437(),
-                symbol_map: HashMap::new(),
-            },
-            TilesetInfo::<GameSym> {
-                image_path: PathBuf::from("assets/terminal-8x8.png"),
-                tile_size: (8, 8).into(),
-                tile_start: (0, 0).into(),
-                tile_gap: (0, 0).into(),
-                font_map: TilesetInfo::<GameSym>::map_code_page_437(),
-                symbol_map: HashMap::new(),
-            },
-            gamesym::urizen_tileset_info(),
-        ],
+        tileset_infos,
+        hot_reload_tilesets: hot_reload,
     };
 
+    // Play in the terminal instead of a window if asked to, e.g. over SSH.
+    #[cfg(not(target_os = "emscripten"))]
+    if std::env::args().any(|arg| arg == "--terminal") {
+        ruggrogue::run_terminal(settings, |inputs, layers, tilesets, window_size| {
+            mode_stack.update(&world, inputs, layers, tilesets, window_size)
+        });
+        return;
+    }
+
     ruggrogue::run(settings, |inputs, layers, tilesets, window_size| {
         mode_stack.update(&world, inputs, layers, tilesets, window_size)
     });
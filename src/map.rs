@@ -12,6 +12,7 @@ use crate::{
     gamesym::GameSym,
     magicnum,
     player::PlayerId,
+    vision::LightMap,
     GameSeed,
 };
 use ruggrogue::util::Color;
@@ -387,6 +388,12 @@ impl Map {
                 if let Some(monster) = self
                     .iter_entities_at(x, y)
                     .find(|id| world.borrow::<View<Monster>>().unwrap().contains(*id))
+                    .filter(|_| {
+                        world
+                            .borrow::<UniqueView<LightMap>>()
+                            .unwrap()
+                            .is_lit((x, y))
+                    })
                 {
                     desc_vec.push(names.get(monster).unwrap().0.clone());
                 }
@@ -455,6 +462,10 @@ impl ruggrogue::PathableMap for Map {
                 .get(&(x, y))
                 .map_or(false, |(block_count, _)| *block_count > 0)
     }
+
+    fn is_wall(&self, x: i32, y: i32) -> bool {
+        matches!(self.get_tile(x, y), &Tile::Wall)
+    }
 }
 
 /// Returns the position to spawn the victory item if the game has progressed far enough.
@@ -32,7 +32,13 @@ impl Messages {
         self.num_highlighted = 0;
     }
 
+    /// Add a plain text message.
     pub fn add(&mut self, msg: String) {
+        self.add_markup(ruggrogue::escape_markup(&msg));
+    }
+
+    /// Add a message containing color markup; see [ruggrogue::parse_markup].
+    pub fn add_markup(&mut self, msg: String) {
         let space_needed = if self.want_separator { 2 } else { 1 };
 
         if self.msg_queue.len() + space_needed >= self.capacity as usize {
@@ -55,7 +61,7 @@ impl Messages {
         self.want_separator = true;
     }
 
-    /// Returns an iterator over messages in reverse order, each with a highlight flag.
+    /// Returns an iterator over messages in reverse order as markup, each with a highlight flag.
     pub fn rev_iter(&self) -> impl Iterator<Item = (&str, bool)> {
         self.msg_queue
             .iter()
@@ -3,7 +3,7 @@ use shipyard::{Get, UniqueView, UniqueViewMut, View, World};
 use crate::{
     chunked::{Camera, ChunkedMapGrid},
     components::{Coord, FieldOfView},
-    damage, experience,
+    damage, effects, experience,
     gamesym::GameSym,
     hunger, item,
     map::Map,
@@ -424,7 +424,9 @@ impl DungeonMode {
         }
 
         self.chunked_map_grid.draw(world, map_grid);
+        self.chunked_map_grid.scroll_animations(world, map_grid);
         render::draw_renderables(&self.chunked_map_grid, world, map_grid);
+        effects::play_effects(&self.chunked_map_grid, world, map_grid);
 
         if self.redraw_msg_frame_grid {
             ui::draw_msg_frame(msg_frame_grid, false);
@@ -8,7 +8,7 @@ use crate::{
 };
 use ruggrogue::{
     util::{Color, Size},
-    InputBuffer, InputEvent, KeyMods, TileGrid, Tileset,
+    InputBuffer, InputEvent, TileGrid, Tileset,
 };
 
 use super::{ModeControl, ModeResult, ModeUpdate};
@@ -147,7 +147,7 @@ impl EquipmentActionMode {
 
     pub fn update(
         &mut self,
-        _world: &World,
+        world: &World,
         inputs: &mut InputBuffer,
         _grids: &[TileGrid<GameSym>],
         _pop_result: &Option<ModeResult>,
@@ -159,8 +159,8 @@ impl EquipmentActionMode {
                 ModeControl::Pop(EquipmentActionModeResult::AppQuit.into()),
                 ModeUpdate::Immediate,
             );
-        } else if let Some(InputEvent::Press(keycode)) = inputs.get_input() {
-            match gamekey::from_keycode(keycode, inputs.get_mods(KeyMods::SHIFT)) {
+        } else if let Some(key) = gamekey::from_input(world, inputs) {
+            match key {
                 GameKey::Up => match self.subsection {
                     SubSection::Actions => {
                         if self.selection > 0 {
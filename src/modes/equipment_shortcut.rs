@@ -11,7 +11,7 @@ use crate::{
 };
 use ruggrogue::{
     util::{Color, Size},
-    InputBuffer, InputEvent, KeyMods, TileGrid, Tileset,
+    InputBuffer, InputEvent, TileGrid, Tileset,
 };
 
 use super::{equipment_action::EquipmentAction, ModeControl, ModeResult, ModeUpdate};
@@ -161,8 +161,8 @@ impl EquipmentShortcutMode {
                     ModeControl::Pop(EquipmentShortcutModeResult::AppQuit.into()),
                     ModeUpdate::Immediate,
                 );
-            } else if let Some(InputEvent::Press(keycode)) = inputs.get_input() {
-                match gamekey::from_keycode(keycode, inputs.get_mods(KeyMods::SHIFT)) {
+            } else if let Some(key) = gamekey::from_input(world, inputs) {
+                match key {
                     GameKey::Up => match self.subsection {
                         SubSection::Items => {
                             if self.selection > 0 {
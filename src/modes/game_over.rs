@@ -11,7 +11,7 @@ use crate::{
 };
 use ruggrogue::{
     util::{Color, Size},
-    InputBuffer, InputEvent, KeyMods, TileGrid, Tileset,
+    InputBuffer, InputEvent, TileGrid, Tileset,
 };
 
 use super::{
@@ -69,8 +69,7 @@ impl GameOverMode {
                 ModeControl::Pop(GameOverModeResult::AppQuit.into()),
                 ModeUpdate::Immediate,
             );
-        } else if let Some(InputEvent::Press(keycode)) = inputs.get_input() {
-            let key = gamekey::from_keycode(keycode, inputs.get_mods(KeyMods::SHIFT));
+        } else if let Some(key) = gamekey::from_input(world, inputs) {
             if matches!(key, GameKey::Confirm | GameKey::Cancel) {
                 let player_alive = world.borrow::<UniqueView<PlayerAlive>>().unwrap().0;
 
@@ -11,7 +11,7 @@ use crate::{
 };
 use ruggrogue::{
     util::{Color, Size},
-    InputBuffer, InputEvent, KeyMods, TileGrid, Tileset,
+    InputBuffer, InputEvent, TileGrid, Tileset,
 };
 
 use super::{
@@ -243,15 +243,14 @@ impl InventoryMode {
                 ModeControl::Pop(InventoryModeResult::AppQuit.into()),
                 ModeUpdate::Immediate,
             )
-        } else if let Some(InputEvent::Press(keycode)) = inputs.get_input() {
+        } else if let Some(key) = gamekey::from_input(world, inputs) {
             let player_id = world.borrow::<UniqueView<PlayerId>>().unwrap();
             let equipments = world.borrow::<View<Equipment>>().unwrap();
             let inventories = world.borrow::<View<Inventory>>().unwrap();
             let player_equipment = equipments.get(player_id.0).unwrap();
             let player_inv = inventories.get(player_id.0).unwrap();
-            let shift = inputs.get_mods(KeyMods::SHIFT);
 
-            match (&self.subsection, gamekey::from_keycode(keycode, shift)) {
+            match (&self.subsection, key) {
                 (SubSection::EquipWeapon, GameKey::Up) => {
                     self.subsection = SubSection::Inventory;
                     self.inv_selection = if player_inv.items.is_empty() {
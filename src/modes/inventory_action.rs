@@ -8,7 +8,7 @@ use crate::{
 };
 use ruggrogue::{
     util::{Color, Size},
-    InputBuffer, InputEvent, KeyMods, TileGrid, Tileset,
+    InputBuffer, InputEvent, TileGrid, Tileset,
 };
 
 use super::{
@@ -233,8 +233,8 @@ impl InventoryActionMode {
                 ModeControl::Pop(InventoryActionModeResult::AppQuit.into()),
                 ModeUpdate::Immediate,
             );
-        } else if let Some(InputEvent::Press(keycode)) = inputs.get_input() {
-            match gamekey::from_keycode(keycode, inputs.get_mods(KeyMods::SHIFT)) {
+        } else if let Some(key) = gamekey::from_input(world, inputs) {
+            match key {
                 GameKey::Down => match self.subsection {
                     SubSection::Actions => {
                         if self.selection < self.actions.len() as i32 - 1 {
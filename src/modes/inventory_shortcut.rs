@@ -11,7 +11,7 @@ use crate::{
 };
 use ruggrogue::{
     util::{Color, Size},
-    InputBuffer, InputEvent, KeyMods, TileGrid, Tileset,
+    InputBuffer, InputEvent, TileGrid, Tileset,
 };
 
 use super::{
@@ -212,8 +212,8 @@ impl InventoryShortcutMode {
                     ModeControl::Pop(InventoryShortcutModeResult::AppQuit.into()),
                     ModeUpdate::Immediate,
                 );
-            } else if let Some(InputEvent::Press(keycode)) = inputs.get_input() {
-                match gamekey::from_keycode(keycode, inputs.get_mods(KeyMods::SHIFT)) {
+            } else if let Some(key) = gamekey::from_input(world, inputs) {
+                match key {
                     GameKey::Up => match self.subsection {
                         SubSection::Items => {
                             if self.selection > 0 {
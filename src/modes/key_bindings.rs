@@ -0,0 +1,374 @@
+use sdl2::keyboard::Keycode;
+use shipyard::{UniqueView, UniqueViewMut, World};
+
+use crate::{
+    gamekey::{self, GameKey, KeyBinding, KeyBindings},
+    gamesym::GameSym,
+    ui::{self, Options},
+};
+use ruggrogue::{
+    util::{Color, Size},
+    InputBuffer, InputEvent, KeyMods, TileGrid, Tileset,
+};
+
+use super::{ModeControl, ModeResult, ModeUpdate};
+
+const TITLE: &str = "< Key bindings >";
+const PROMPT: &str = "Enter: add key   Delete: clear keys";
+const CAPTURE_PROMPT: &str = "Press a key to bind, Escape to cancel";
+const RESET: &str = "[ Reset to defaults ]";
+const BACK: &str = "[ Back ]";
+const LABEL_WIDTH: usize = longest_label();
+const KEYS_WIDTH: usize = 28;
+
+const ACTIONS: [(GameKey, &str); 25] = [
+    (GameKey::Up, "Up"),
+    (GameKey::Down, "Down"),
+    (GameKey::Left, "Left"),
+    (GameKey::Right, "Right"),
+    (GameKey::UpLeft, "Up-left"),
+    (GameKey::UpRight, "Up-right"),
+    (GameKey::DownLeft, "Down-left"),
+    (GameKey::DownRight, "Down-right"),
+    (GameKey::PageUp, "Page up"),
+    (GameKey::PageDown, "Page down"),
+    (GameKey::Home, "Home"),
+    (GameKey::End, "End"),
+    (GameKey::Wait, "Wait"),
+    (GameKey::Descend, "Descend"),
+    (GameKey::Cancel, "Cancel"),
+    (GameKey::Confirm, "Confirm"),
+    (GameKey::ViewMap, "View map"),
+    (GameKey::PickUp, "Pick up"),
+    (GameKey::Inventory, "Inventory"),
+    (GameKey::EquipItem, "Equip item"),
+    (GameKey::RemoveItem, "Remove item"),
+    (GameKey::UseItem, "Use item"),
+    (GameKey::DropItem, "Drop item"),
+    (GameKey::Screenshot, "Screenshot"),
+    (GameKey::TextScreenshot, "Text screenshot"),
+];
+
+/// Width of the longest label in [ACTIONS], so the keys bound to each action line up after it.
+const fn longest_label() -> usize {
+    let mut longest = 0;
+    let mut i = 0;
+
+    while i < ACTIONS.len() {
+        if ACTIONS[i].1.len() > longest {
+            longest = ACTIONS[i].1.len();
+        }
+        i += 1;
+    }
+
+    longest
+}
+
+pub enum KeyBindingsModeResult {
+    AppQuit,
+    Closed,
+}
+
+enum SubSection {
+    Actions,
+    Reset,
+    Back,
+}
+
+pub struct KeyBindingsMode {
+    subsection: SubSection,
+    selection: i32,
+    capturing: bool,
+}
+
+/// A screen listing each game key along with the keyboard keys bound to it, allowing keys to be
+/// added, cleared and reset to their defaults.  Changes are saved to the key bindings file when
+/// the screen is closed.
+///
+/// The arrow keys, Enter and Escape always work here, and the menu keys can't lose their last
+/// binding, so that bad bindings can't lock the player out of fixing them.
+impl KeyBindingsMode {
+    pub fn new() -> Self {
+        Self {
+            subsection: SubSection::Actions,
+            selection: 0,
+            capturing: false,
+        }
+    }
+
+    /// Returns true while waiting for a key to bind, when every key press belongs to this mode.
+    pub fn is_capturing(&self) -> bool {
+        self.capturing
+    }
+
+    pub fn prepare_grids(
+        &self,
+        world: &World,
+        grids: &mut Vec<TileGrid<GameSym>>,
+        tilesets: &[Tileset<GameSym>],
+        window_size: Size,
+    ) {
+        let Options {
+            font, text_zoom, ..
+        } = *world.borrow::<UniqueView<Options>>().unwrap();
+        let tileset = &tilesets.get(font as usize).unwrap_or(&tilesets[0]);
+        let new_grid_size = Size {
+            w: (4 + (LABEL_WIDTH + 1 + KEYS_WIDTH).max(CAPTURE_PROMPT.len())) as u32,
+            h: (10 + ACTIONS.len() as u32)
+                .min(window_size.h / (tileset.tile_height() * text_zoom))
+                .max(11),
+        };
+
+        if !grids.is_empty() {
+            grids[0].resize(new_grid_size);
+        } else {
+            grids.push(TileGrid::new(new_grid_size, tilesets, font as usize));
+            grids[0].view.clear_color = None;
+        }
+
+        grids[0].set_tileset(tilesets, font as usize);
+        grids[0].view_centered(tilesets, text_zoom, (0, 0).into(), window_size);
+        grids[0].view.zoom = text_zoom;
+    }
+
+    /// The height of the action list as an i32 for convenience.
+    fn action_list_height(grid: &TileGrid<GameSym>) -> i32 {
+        grid.height().saturating_sub(10).max(1).min(i32::MAX as u32) as i32
+    }
+
+    fn close(world: &World) -> (ModeControl, ModeUpdate) {
+        if let Err(e) = world.borrow::<UniqueView<KeyBindings>>().unwrap().save() {
+            eprintln!("Warning: key_bindings::close: {}", e);
+        }
+
+        (
+            ModeControl::Pop(KeyBindingsModeResult::Closed.into()),
+            ModeUpdate::Immediate,
+        )
+    }
+
+    fn capture(&mut self, world: &World, inputs: &InputBuffer, keycode: Keycode) {
+        match keycode {
+            Keycode::Escape => self.capturing = false,
+            Keycode::LShift
+            | Keycode::RShift
+            | Keycode::LCtrl
+            | Keycode::RCtrl
+            | Keycode::LAlt
+            | Keycode::RAlt => {}
+            _ => {
+                world
+                    .borrow::<UniqueViewMut<KeyBindings>>()
+                    .unwrap()
+                    .bind(KeyBinding {
+                        key: keycode,
+                        shift: inputs.get_mods(KeyMods::SHIFT),
+                        ctrl: inputs.get_mods(KeyMods::CTRL),
+                        alt: inputs.get_mods(KeyMods::ALT),
+                        game_key: ACTIONS[self.selection as usize].0,
+                    });
+                self.capturing = false;
+            }
+        }
+    }
+
+    pub fn update(
+        &mut self,
+        world: &World,
+        inputs: &mut InputBuffer,
+        grids: &[TileGrid<GameSym>],
+        _pop_result: &Option<ModeResult>,
+    ) -> (ModeControl, ModeUpdate) {
+        let list_height = Self::action_list_height(&grids[0]);
+
+        inputs.prepare_input();
+
+        if let Some(InputEvent::AppQuit) = inputs.get_input() {
+            return (
+                ModeControl::Pop(KeyBindingsModeResult::AppQuit.into()),
+                ModeUpdate::Immediate,
+            );
+        }
+
+        if self.capturing {
+            if let Some(InputEvent::Press(keycode)) = inputs.get_input() {
+                self.capture(world, inputs, keycode);
+            }
+            return (ModeControl::Stay, ModeUpdate::WaitForEvent);
+        }
+
+        let key = match inputs.get_input() {
+            Some(InputEvent::Press(Keycode::Up)) => Some(GameKey::Up),
+            Some(InputEvent::Press(Keycode::Down)) => Some(GameKey::Down),
+            Some(InputEvent::Press(Keycode::Return)) => Some(GameKey::Confirm),
+            Some(InputEvent::Press(Keycode::Escape)) => Some(GameKey::Cancel),
+            Some(InputEvent::Press(Keycode::Delete | Keycode::Backspace)) => {
+                if matches!(self.subsection, SubSection::Actions) {
+                    world
+                        .borrow::<UniqueViewMut<KeyBindings>>()
+                        .unwrap()
+                        .unbind_all(ACTIONS[self.selection as usize].0);
+                }
+                None
+            }
+            _ => gamekey::from_input(world, inputs),
+        };
+
+        if let Some(key) = key {
+            match key {
+                GameKey::Up => match self.subsection {
+                    SubSection::Actions if self.selection > 0 => self.selection -= 1,
+                    SubSection::Actions => self.subsection = SubSection::Back,
+                    SubSection::Reset => {
+                        self.subsection = SubSection::Actions;
+                        self.selection = ACTIONS.len() as i32 - 1;
+                    }
+                    SubSection::Back => self.subsection = SubSection::Reset,
+                },
+                GameKey::Down => match self.subsection {
+                    SubSection::Actions if self.selection + 1 < ACTIONS.len() as i32 => {
+                        self.selection += 1
+                    }
+                    SubSection::Actions => self.subsection = SubSection::Reset,
+                    SubSection::Reset => self.subsection = SubSection::Back,
+                    SubSection::Back => {
+                        self.subsection = SubSection::Actions;
+                        self.selection = 0;
+                    }
+                },
+                GameKey::PageUp if matches!(self.subsection, SubSection::Actions) => {
+                    self.selection = (self.selection - list_height).max(0);
+                }
+                GameKey::PageDown if matches!(self.subsection, SubSection::Actions) => {
+                    self.selection = (self.selection + list_height).min(ACTIONS.len() as i32 - 1);
+                }
+                GameKey::Home => {
+                    self.subsection = SubSection::Actions;
+                    self.selection = 0;
+                }
+                GameKey::End => {
+                    self.subsection = SubSection::Actions;
+                    self.selection = ACTIONS.len() as i32 - 1;
+                }
+                GameKey::Confirm => match self.subsection {
+                    SubSection::Actions => self.capturing = true,
+                    SubSection::Reset => world
+                        .borrow::<UniqueViewMut<KeyBindings>>()
+                        .unwrap()
+                        .reset(),
+                    SubSection::Back => {
+                        inputs.clear_input();
+                        return Self::close(world);
+                    }
+                },
+                GameKey::Cancel => {
+                    inputs.clear_input();
+                    return Self::close(world);
+                }
+                _ => {}
+            }
+        }
+
+        (ModeControl::Stay, ModeUpdate::WaitForEvent)
+    }
+
+    pub fn draw(&self, world: &World, grids: &mut [TileGrid<GameSym>], active: bool) {
+        let grid = &mut grids[0];
+        let width = grid.width();
+        let height = grid.height();
+        let fg = Color::WHITE;
+        let bg = Color::BLACK;
+        let selected_bg = ui::SELECTED_BG;
+        let key_bindings = world.borrow::<UniqueView<KeyBindings>>().unwrap();
+
+        grid.view.color_mod = if active { Color::WHITE } else { Color::GRAY };
+
+        grid.draw_box((0, 0), (width, height), fg, bg);
+        grid.print_color((2, 0), TITLE, true, Color::YELLOW, bg);
+        if self.capturing {
+            grid.print_color((2, 2), CAPTURE_PROMPT, true, Color::YELLOW, bg);
+        } else {
+            grid.print((2, 2), PROMPT);
+        }
+
+        let list_height = Self::action_list_height(grid);
+        let list_offset = (self.selection - (list_height - 1) / 2)
+            .min(ACTIONS.len() as i32 - list_height)
+            .max(0);
+
+        if ACTIONS.len() as i32 > list_height {
+            grid.draw_bar(
+                true,
+                (width as i32 - 1, 4),
+                list_height,
+                list_offset,
+                list_height,
+                ACTIONS.len() as i32,
+                fg,
+                bg,
+            );
+        }
+
+        for (i, (game_key, label)) in ACTIONS
+            .iter()
+            .enumerate()
+            .skip(list_offset as usize)
+            .take(list_height as usize)
+        {
+            let y = 4 + i as i32 - list_offset;
+            let keys = key_bindings
+                .bindings_for(*game_key)
+                .map(KeyBinding::describe)
+                .collect::<Vec<_>>()
+                .join(", ");
+            let keys: String = if keys.chars().count() > KEYS_WIDTH {
+                keys.chars()
+                    .take(KEYS_WIDTH - 3)
+                    .chain("...".chars())
+                    .collect()
+            } else {
+                keys
+            };
+            let selected =
+                matches!(self.subsection, SubSection::Actions) && i as i32 == self.selection;
+
+            grid.print_color(
+                (2, y),
+                &format!("{:>width$}", label, width = LABEL_WIDTH),
+                true,
+                fg,
+                if selected { selected_bg } else { bg },
+            );
+            grid.print_color(
+                (3 + LABEL_WIDTH as i32, y),
+                if keys.is_empty() { "-" } else { &keys },
+                true,
+                if keys.is_empty() { Color::GRAY } else { fg },
+                bg,
+            );
+        }
+
+        grid.print_color(
+            (2, height as i32 - 5),
+            RESET,
+            true,
+            fg,
+            if matches!(self.subsection, SubSection::Reset) {
+                selected_bg
+            } else {
+                bg
+            },
+        );
+        grid.print_color(
+            (2, height as i32 - 3),
+            BACK,
+            true,
+            fg,
+            if matches!(self.subsection, SubSection::Back) {
+                selected_bg
+            } else {
+                bg
+            },
+        );
+    }
+}
@@ -0,0 +1,336 @@
+use sdl2::keyboard::Keycode;
+use shipyard::{UniqueView, World};
+
+use crate::{
+    gamekey::{self, GameKey},
+    gamesym::GameSym,
+    saveload::{self, SaveSlotInfo},
+    ui::{self, Options},
+};
+use ruggrogue::{
+    util::{Color, Size},
+    InputBuffer, InputEvent, TileGrid, Tileset,
+};
+
+use super::{
+    message_box::{MessageBoxMode, MessageBoxModeResult},
+    yes_no_dialog::{YesNoDialogMode, YesNoDialogModeResult},
+    ModeControl, ModeResult, ModeUpdate,
+};
+
+const TITLE: &str = "< Load Game >";
+const PROMPT: &str = "Enter: load   Delete: delete save";
+const COLUMNS: &str = "Name            Depth Level    Turns  Last played     ";
+const NAME_WIDTH: usize = 15;
+/// Width of a timestamp from [format_timestamp].
+const TIMESTAMP_WIDTH: usize = 16;
+/// Width of each row of the slot list, matching the columns of [COLUMNS].
+const ROW_WIDTH: usize = NAME_WIDTH + 1 + 5 + 1 + 5 + 1 + 8 + 2 + TIMESTAMP_WIDTH;
+
+pub enum LoadGameModeResult {
+    AppQuit,
+    Cancelled,
+    Loaded,
+}
+
+pub struct LoadGameMode {
+    slots: Vec<SaveSlotInfo>,
+    selection: i32,
+}
+
+/// Format seconds since the Unix epoch as a UTC date and time, e.g. "2021-06-01 13:45".
+fn format_timestamp(secs: u64) -> String {
+    let days = (secs / 86400) as i64;
+    let minutes_of_day = secs % 86400 / 60;
+
+    // Convert days since the epoch to a civil date in the proleptic Gregorian calendar.
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}",
+        year,
+        month,
+        day,
+        minutes_of_day / 60,
+        minutes_of_day % 60,
+    )
+}
+
+/// A menu listing all save slots, showing depth, player level, turn count and the time each one
+/// was last played.  The selected slot can be loaded or deleted.
+impl LoadGameMode {
+    pub fn new() -> Self {
+        Self {
+            slots: saveload::list_save_slots(),
+            selection: 0,
+        }
+    }
+
+    pub fn prepare_grids(
+        &self,
+        world: &World,
+        grids: &mut Vec<TileGrid<GameSym>>,
+        tilesets: &[Tileset<GameSym>],
+        window_size: Size,
+    ) {
+        let Options {
+            font, text_zoom, ..
+        } = *world.borrow::<UniqueView<Options>>().unwrap();
+        let tileset = &tilesets.get(font as usize).unwrap_or(&tilesets[0]);
+        let new_grid_size = Size {
+            w: 4 + ROW_WIDTH.max(PROMPT.len()).max(TITLE.len()) as u32,
+            h: (8 + self.slots.len() as u32)
+                .min(window_size.h / (tileset.tile_height() * text_zoom))
+                .max(9),
+        };
+
+        if !grids.is_empty() {
+            grids[0].resize(new_grid_size);
+        } else {
+            grids.push(TileGrid::new(new_grid_size, tilesets, font as usize));
+            grids[0].view.clear_color = None;
+        }
+
+        grids[0].set_tileset(tilesets, font as usize);
+        grids[0].view_centered(tilesets, text_zoom, (0, 0).into(), window_size);
+        grids[0].view.zoom = text_zoom;
+    }
+
+    /// The height of the slot list as an i32 for convenience.
+    fn slot_list_height(grid: &TileGrid<GameSym>) -> i32 {
+        grid.height().saturating_sub(8).max(1).min(i32::MAX as u32) as i32
+    }
+
+    fn load_selected(&self, world: &World) -> (ModeControl, ModeUpdate) {
+        let slot = &self.slots[self.selection as usize].slot;
+
+        match saveload::load_game(world, slot) {
+            Ok(_) => (
+                ModeControl::Pop(LoadGameModeResult::Loaded.into()),
+                ModeUpdate::Immediate,
+            ),
+            Err(e) => {
+                let mut msg = vec!["Failed to load game:".to_string(), "".to_string()];
+
+                msg.extend(ruggrogue::word_wrap(&format!("{}", e), 78).map(String::from));
+
+                (
+                    ModeControl::Push(MessageBoxMode::new(msg).into()),
+                    ModeUpdate::Immediate,
+                )
+            }
+        }
+    }
+
+    pub fn update(
+        &mut self,
+        world: &World,
+        inputs: &mut InputBuffer,
+        grids: &[TileGrid<GameSym>],
+        pop_result: &Option<ModeResult>,
+    ) -> (ModeControl, ModeUpdate) {
+        if let Some(result) = pop_result {
+            return match result {
+                ModeResult::MessageBoxModeResult(result) => match result {
+                    MessageBoxModeResult::AppQuit => (
+                        ModeControl::Pop(LoadGameModeResult::AppQuit.into()),
+                        ModeUpdate::Immediate,
+                    ),
+                    MessageBoxModeResult::Done => (ModeControl::Stay, ModeUpdate::WaitForEvent),
+                },
+                ModeResult::YesNoDialogModeResult(result) => match result {
+                    YesNoDialogModeResult::AppQuit => (
+                        ModeControl::Pop(LoadGameModeResult::AppQuit.into()),
+                        ModeUpdate::Immediate,
+                    ),
+                    YesNoDialogModeResult::Yes => {
+                        saveload::delete_save_slot(&self.slots[self.selection as usize].slot);
+                        self.slots = saveload::list_save_slots();
+                        self.selection = self
+                            .selection
+                            .min(self.slots.len().saturating_sub(1) as i32);
+
+                        inputs.clear_input();
+                        if self.slots.is_empty() {
+                            (
+                                ModeControl::Pop(LoadGameModeResult::Cancelled.into()),
+                                ModeUpdate::Immediate,
+                            )
+                        } else {
+                            (ModeControl::Stay, ModeUpdate::Immediate)
+                        }
+                    }
+                    YesNoDialogModeResult::No => (ModeControl::Stay, ModeUpdate::WaitForEvent),
+                },
+                _ => unreachable!(),
+            };
+        }
+
+        if self.slots.is_empty() {
+            return (
+                ModeControl::Pop(LoadGameModeResult::Cancelled.into()),
+                ModeUpdate::Immediate,
+            );
+        }
+
+        let list_height = Self::slot_list_height(&grids[0]);
+
+        inputs.prepare_input();
+
+        if let Some(InputEvent::AppQuit) = inputs.get_input() {
+            return (
+                ModeControl::Pop(LoadGameModeResult::AppQuit.into()),
+                ModeUpdate::Immediate,
+            );
+        } else if let Some(InputEvent::Press(Keycode::Delete | Keycode::Backspace)) =
+            inputs.get_input()
+        {
+            let slot = &self.slots[self.selection as usize];
+            let name = slot.header.as_ref().map_or(&slot.slot, |h| &h.name);
+
+            inputs.clear_input();
+            return (
+                ModeControl::Push(
+                    YesNoDialogMode::new(format!("Delete the save for {}?", name), false).into(),
+                ),
+                ModeUpdate::Immediate,
+            );
+        } else if let Some(key) = gamekey::from_input(world, inputs) {
+            match key {
+                GameKey::Up => {
+                    if self.selection > 0 {
+                        self.selection -= 1;
+                    } else {
+                        self.selection = self.slots.len() as i32 - 1;
+                    }
+                }
+                GameKey::Down => {
+                    if self.selection < self.slots.len() as i32 - 1 {
+                        self.selection += 1;
+                    } else {
+                        self.selection = 0;
+                    }
+                }
+                GameKey::PageUp => self.selection = (self.selection - list_height).max(0),
+                GameKey::PageDown => {
+                    self.selection = (self.selection + list_height).min(self.slots.len() as i32 - 1)
+                }
+                GameKey::Home => self.selection = 0,
+                GameKey::End => self.selection = self.slots.len() as i32 - 1,
+                GameKey::Confirm => {
+                    inputs.clear_input();
+                    return self.load_selected(world);
+                }
+                GameKey::Cancel => {
+                    inputs.clear_input();
+                    return (
+                        ModeControl::Pop(LoadGameModeResult::Cancelled.into()),
+                        ModeUpdate::Immediate,
+                    );
+                }
+                _ => {}
+            }
+        }
+
+        (ModeControl::Stay, ModeUpdate::WaitForEvent)
+    }
+
+    pub fn draw(&self, _world: &World, grids: &mut [TileGrid<GameSym>], active: bool) {
+        let grid = &mut grids[0];
+        let width = grid.width();
+        let height = grid.height();
+        let fg = Color::WHITE;
+        let bg = Color::BLACK;
+        let selected_bg = ui::SELECTED_BG;
+
+        grid.view.color_mod = if active { Color::WHITE } else { Color::GRAY };
+
+        grid.draw_box((0, 0), (width, height), fg, bg);
+        grid.print_color((2, 0), TITLE, true, Color::YELLOW, bg);
+        grid.print((2, 2), PROMPT);
+        grid.print_color((2, 4), COLUMNS, true, Color::GRAY, bg);
+
+        let list_height = Self::slot_list_height(grid);
+        let list_offset = (self.selection - (list_height - 1) / 2)
+            .min(self.slots.len() as i32 - list_height)
+            .max(0);
+
+        if self.slots.len() as i32 > list_height {
+            grid.draw_bar(
+                true,
+                (width as i32 - 1, 6),
+                list_height,
+                list_offset,
+                list_height,
+                self.slots.len() as i32,
+                fg,
+                bg,
+            );
+        }
+
+        for (i, slot) in self
+            .slots
+            .iter()
+            .enumerate()
+            .skip(list_offset as usize)
+            .take(list_height as usize)
+        {
+            let line = if let Some(header) = &slot.header {
+                format!(
+                    "{:<name_width$} {:>5} {:>5} {:>8}  {:<timestamp_width$}",
+                    header.name.chars().take(NAME_WIDTH).collect::<String>(),
+                    header.depth,
+                    header.level,
+                    header.turns,
+                    format_timestamp(header.saved_at),
+                    name_width = NAME_WIDTH,
+                    timestamp_width = TIMESTAMP_WIDTH,
+                )
+            } else {
+                format!(
+                    "{:<name_width$} {:>5} {:>5} {:>8}  {:<timestamp_width$}",
+                    slot.slot.chars().take(NAME_WIDTH).collect::<String>(),
+                    "?",
+                    "?",
+                    "?",
+                    "?",
+                    name_width = NAME_WIDTH,
+                    timestamp_width = TIMESTAMP_WIDTH,
+                )
+            };
+
+            grid.print_color(
+                (2, 6 + i as i32 - list_offset),
+                &line,
+                true,
+                fg,
+                if i as i32 == self.selection {
+                    selected_bg
+                } else {
+                    bg
+                },
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn columns_line_up_with_rows() {
+        assert_eq!(COLUMNS.len(), ROW_WIDTH);
+        assert_eq!(format_timestamp(0), "1970-01-01 00:00");
+        assert_eq!(format_timestamp(1622555100), "2021-06-01 13:45");
+        assert_eq!(format_timestamp(253402300799).len(), TIMESTAMP_WIDTH);
+    }
+}
@@ -7,7 +7,7 @@ use crate::{
 };
 use ruggrogue::{
     util::{Color, Size},
-    InputBuffer, InputEvent, KeyMods, TileGrid, Tileset,
+    InputBuffer, InputEvent, TileGrid, Tileset,
 };
 
 use super::{ModeControl, ModeResult, ModeUpdate};
@@ -59,7 +59,7 @@ impl MessageBoxMode {
 
     pub fn update(
         &mut self,
-        _world: &World,
+        world: &World,
         inputs: &mut InputBuffer,
         _grids: &[TileGrid<GameSym>],
         _pop_result: &Option<ModeResult>,
@@ -71,8 +71,7 @@ impl MessageBoxMode {
                 ModeControl::Pop(MessageBoxModeResult::AppQuit.into()),
                 ModeUpdate::Immediate,
             );
-        } else if let Some(InputEvent::Press(keycode)) = inputs.get_input() {
-            let key = gamekey::from_keycode(keycode, inputs.get_mods(KeyMods::SHIFT));
+        } else if let Some(key) = gamekey::from_input(world, inputs) {
             if matches!(key, GameKey::Confirm | GameKey::Cancel) {
                 inputs.clear_input();
                 return (
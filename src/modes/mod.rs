@@ -31,17 +31,25 @@ pub mod game_over;
 pub mod inventory;
 pub mod inventory_action;
 pub mod inventory_shortcut;
+pub mod key_bindings;
+pub mod load_game;
 pub mod message_box;
 pub mod options_menu;
 pub mod pick_up_menu;
 pub mod target;
+pub mod text_entry;
 pub mod title;
 pub mod view_map;
 pub mod yes_no_dialog;
 
 use shipyard::World;
+use std::path::PathBuf;
 
-use crate::gamesym::GameSym;
+use crate::{
+    gamekey::{self, GameKey},
+    gamesym::GameSym,
+    screenshot,
+};
 use ruggrogue::{util::Size, InputBuffer, RunControl, TileGrid, TileGridLayer, Tileset};
 
 use app_quit_dialog::{AppQuitDialogMode, AppQuitDialogModeResult};
@@ -52,10 +60,13 @@ use game_over::{GameOverMode, GameOverModeResult};
 use inventory::{InventoryMode, InventoryModeResult};
 use inventory_action::{InventoryActionMode, InventoryActionModeResult};
 use inventory_shortcut::{InventoryShortcutMode, InventoryShortcutModeResult};
+use key_bindings::{KeyBindingsMode, KeyBindingsModeResult};
+use load_game::{LoadGameMode, LoadGameModeResult};
 use message_box::{MessageBoxMode, MessageBoxModeResult};
 use options_menu::{OptionsMenuMode, OptionsMenuModeResult};
 use pick_up_menu::{PickUpMenuMode, PickUpMenuModeResult};
 use target::{TargetMode, TargetModeResult};
+use text_entry::{TextEntryMode, TextEntryModeResult};
 use title::{TitleMode, TitleModeResult};
 use view_map::{ViewMapMode, ViewMapModeResult};
 use yes_no_dialog::{YesNoDialogMode, YesNoDialogModeResult};
@@ -84,10 +95,13 @@ pub enum Mode {
     InventoryMode(InventoryMode),
     InventoryActionMode(InventoryActionMode),
     InventoryShortcutMode(InventoryShortcutMode),
+    KeyBindingsMode(KeyBindingsMode),
+    LoadGameMode(LoadGameMode),
     MessageBoxMode(MessageBoxMode),
     OptionsMenuMode(OptionsMenuMode),
     PickUpMenuMode(PickUpMenuMode),
     TargetMode(TargetMode),
+    TextEntryMode(TextEntryMode),
     TitleMode(TitleMode),
     ViewMapMode(ViewMapMode),
     YesNoDialogMode(YesNoDialogMode),
@@ -101,10 +115,13 @@ impl_from!(Mode, GameOverMode);
 impl_from!(Mode, InventoryMode);
 impl_from!(Mode, InventoryActionMode);
 impl_from!(Mode, InventoryShortcutMode);
+impl_from!(Mode, KeyBindingsMode);
+impl_from!(Mode, LoadGameMode);
 impl_from!(Mode, MessageBoxMode);
 impl_from!(Mode, OptionsMenuMode);
 impl_from!(Mode, PickUpMenuMode);
 impl_from!(Mode, TargetMode);
+impl_from!(Mode, TextEntryMode);
 impl_from!(Mode, TitleMode);
 impl_from!(Mode, ViewMapMode);
 impl_from!(Mode, YesNoDialogMode);
@@ -123,10 +140,13 @@ pub enum ModeResult {
     InventoryModeResult(InventoryModeResult),
     InventoryActionModeResult(InventoryActionModeResult),
     InventoryShortcutModeResult(InventoryShortcutModeResult),
+    KeyBindingsModeResult(KeyBindingsModeResult),
+    LoadGameModeResult(LoadGameModeResult),
     MessageBoxModeResult(MessageBoxModeResult),
     OptionsMenuModeResult(OptionsMenuModeResult),
     PickUpMenuModeResult(PickUpMenuModeResult),
     TargetModeResult(TargetModeResult),
+    TextEntryModeResult(TextEntryModeResult),
     TitleModeResult(TitleModeResult),
     ViewMapModeResult(ViewMapModeResult),
     YesNoDialogModeResult(YesNoDialogModeResult),
@@ -140,10 +160,13 @@ impl_from!(ModeResult, GameOverModeResult);
 impl_from!(ModeResult, InventoryModeResult);
 impl_from!(ModeResult, InventoryActionModeResult);
 impl_from!(ModeResult, InventoryShortcutModeResult);
+impl_from!(ModeResult, KeyBindingsModeResult);
+impl_from!(ModeResult, LoadGameModeResult);
 impl_from!(ModeResult, MessageBoxModeResult);
 impl_from!(ModeResult, OptionsMenuModeResult);
 impl_from!(ModeResult, PickUpMenuModeResult);
 impl_from!(ModeResult, TargetModeResult);
+impl_from!(ModeResult, TextEntryModeResult);
 impl_from!(ModeResult, TitleModeResult);
 impl_from!(ModeResult, ViewMapModeResult);
 impl_from!(ModeResult, YesNoDialogModeResult);
@@ -195,10 +218,13 @@ impl Mode {
             Mode::InventoryMode(x) => x.prepare_grids(world, grids, tilesets, window_size),
             Mode::InventoryActionMode(x) => x.prepare_grids(world, grids, tilesets, window_size),
             Mode::InventoryShortcutMode(x) => x.prepare_grids(world, grids, tilesets, window_size),
+            Mode::KeyBindingsMode(x) => x.prepare_grids(world, grids, tilesets, window_size),
+            Mode::LoadGameMode(x) => x.prepare_grids(world, grids, tilesets, window_size),
             Mode::MessageBoxMode(x) => x.prepare_grids(world, grids, tilesets, window_size),
             Mode::OptionsMenuMode(x) => x.prepare_grids(world, grids, tilesets, window_size),
             Mode::PickUpMenuMode(x) => x.prepare_grids(world, grids, tilesets, window_size),
             Mode::TargetMode(x) => x.prepare_grids(world, grids, tilesets, window_size),
+            Mode::TextEntryMode(x) => x.prepare_grids(world, grids, tilesets, window_size),
             Mode::TitleMode(x) => x.prepare_grids(world, grids, tilesets, window_size),
             Mode::ViewMapMode(x) => x.prepare_grids(world, grids, tilesets, window_size),
             Mode::YesNoDialogMode(x) => x.prepare_grids(world, grids, tilesets, window_size),
@@ -221,10 +247,13 @@ impl Mode {
             Mode::InventoryMode(x) => x.update(world, inputs, grids, pop_result),
             Mode::InventoryActionMode(x) => x.update(world, inputs, grids, pop_result),
             Mode::InventoryShortcutMode(x) => x.update(world, inputs, grids, pop_result),
+            Mode::KeyBindingsMode(x) => x.update(world, inputs, grids, pop_result),
+            Mode::LoadGameMode(x) => x.update(world, inputs, grids, pop_result),
             Mode::MessageBoxMode(x) => x.update(world, inputs, grids, pop_result),
             Mode::OptionsMenuMode(x) => x.update(world, inputs, grids, pop_result),
             Mode::PickUpMenuMode(x) => x.update(world, inputs, grids, pop_result),
             Mode::TargetMode(x) => x.update(world, inputs, grids, pop_result),
+            Mode::TextEntryMode(x) => x.update(world, inputs, grids, pop_result),
             Mode::TitleMode(x) => x.update(world, inputs, grids, pop_result),
             Mode::ViewMapMode(x) => x.update(world, inputs, grids, pop_result),
             Mode::YesNoDialogMode(x) => x.update(world, inputs, grids, pop_result),
@@ -241,10 +270,13 @@ impl Mode {
             Mode::InventoryMode(x) => x.draw(world, grids, active),
             Mode::InventoryActionMode(x) => x.draw(world, grids, active),
             Mode::InventoryShortcutMode(x) => x.draw(world, grids, active),
+            Mode::KeyBindingsMode(x) => x.draw(world, grids, active),
+            Mode::LoadGameMode(x) => x.draw(world, grids, active),
             Mode::MessageBoxMode(x) => x.draw(world, grids, active),
             Mode::OptionsMenuMode(x) => x.draw(world, grids, active),
             Mode::PickUpMenuMode(x) => x.draw(world, grids, active),
             Mode::TargetMode(x) => x.draw(world, grids, active),
+            Mode::TextEntryMode(x) => x.draw(world, grids, active),
             Mode::TitleMode(x) => x.draw(world, grids, active),
             Mode::ViewMapMode(x) => x.draw(world, grids, active),
             Mode::YesNoDialogMode(x) => x.draw(world, grids, active),
@@ -262,10 +294,13 @@ impl Mode {
             Mode::InventoryMode(_) => true,
             Mode::InventoryActionMode(_) => true,
             Mode::InventoryShortcutMode(_) => true,
+            Mode::KeyBindingsMode(_) => true,
+            Mode::LoadGameMode(_) => true,
             Mode::MessageBoxMode(_) => true,
             Mode::OptionsMenuMode(_) => true,
             Mode::PickUpMenuMode(_) => true,
             Mode::TargetMode(_) => false,
+            Mode::TextEntryMode(_) => true,
             Mode::TitleMode(_) => false,
             Mode::ViewMapMode(_) => false,
             Mode::YesNoDialogMode(_) => true,
@@ -281,6 +316,7 @@ impl Mode {
 pub struct ModeStack {
     stack: Vec<Mode>,
     pop_result: Option<ModeResult>,
+    png_screenshot: Option<PathBuf>,
 }
 
 impl ModeStack {
@@ -289,6 +325,7 @@ impl ModeStack {
         Self {
             stack,
             pop_result: None,
+            png_screenshot: None,
         }
     }
 
@@ -315,6 +352,32 @@ impl ModeStack {
             }
         }
 
+        // The main loop saves PNG screenshots after the update that requested them.
+        if let Some(path) = self.png_screenshot.take() {
+            screenshot::sync_png(&path);
+        }
+
+        // Take screenshots here so they work in every mode, except when the top mode wants every
+        // key press for itself.
+        let keys_captured = inputs.is_text_input_active()
+            || matches!(self.stack.last(), Some(Mode::KeyBindingsMode(m)) if m.is_capturing());
+
+        if !keys_captured {
+            inputs.prepare_input();
+
+            match gamekey::from_input(world, inputs) {
+                Some(GameKey::Screenshot) => {
+                    self.png_screenshot = screenshot::request_png(inputs);
+                    inputs.clear_input();
+                }
+                Some(GameKey::TextScreenshot) => {
+                    screenshot::save_text(layers);
+                    inputs.clear_input();
+                }
+                _ => {}
+            }
+        }
+
         while !self.stack.is_empty() {
             // Prepare grids for modes, starting from the lowest visible mode.
             let prepare_grids_from = self
@@ -388,6 +451,10 @@ impl ModeStack {
             match mode_update {
                 ModeUpdate::Immediate => (),
                 ModeUpdate::Update => return RunControl::Update,
+                // Come back next frame to sync a requested screenshot once it's saved.
+                ModeUpdate::WaitForEvent if self.png_screenshot.is_some() => {
+                    return RunControl::Update
+                }
                 ModeUpdate::WaitForEvent => return RunControl::WaitForEvent,
             }
         }
@@ -3,21 +3,22 @@ use shipyard::{UniqueView, UniqueViewMut, World};
 use crate::{
     gamekey::{self, GameKey},
     gamesym::GameSym,
-    ui::{self, Options},
+    saveload,
+    ui::{self, Options, NUM_FONTS},
 };
 use ruggrogue::{
     util::{Color, Size},
-    InputBuffer, InputEvent, KeyMods, TileGrid, Tileset,
+    InputBuffer, InputEvent, TileGrid, Tileset, WindowMode,
 };
 
 use super::{
+    key_bindings::{KeyBindingsMode, KeyBindingsModeResult},
     yes_no_dialog::{YesNoDialogMode, YesNoDialogModeResult},
     ModeControl, ModeResult, ModeUpdate,
 };
 
 const TILESET_LABEL: &str = "  Tileset:";
 const FONT_LABEL: &str = "     Font:";
-const NUM_FONTS: u32 = 2;
 const TILESET_NAMES: [&str; 3] = ["GohuFont", "Terminal", "Urizen"];
 const UNKNOWN_TILESET_NAME: &str = "???";
 const MAP_ZOOM_LABEL: &str = " Map zoom:";
@@ -26,6 +27,18 @@ const ZOOM_1X_ON: &str = "[1x]";
 const ZOOM_1X_OFF: &str = " 1x ";
 const ZOOM_2X_ON: &str = "[2x]";
 const ZOOM_2X_OFF: &str = " 2x ";
+const WINDOW_MODE_LABEL: &str = "   Window:";
+const WINDOW_MODES: [(WindowMode, &str); 3] = [
+    (WindowMode::Windowed, "Windowed"),
+    (WindowMode::Borderless, "Borderless"),
+    (WindowMode::Fullscreen, "Fullscreen"),
+];
+const INTEGER_SCALE_LABEL: &str = "Int scale:";
+const INTEGER_SCALE_OFF_ON: &str = "[Off]";
+const INTEGER_SCALE_OFF_OFF: &str = " Off ";
+const INTEGER_SCALE_ON_ON: &str = "[On]";
+const INTEGER_SCALE_ON_OFF: &str = " On ";
+const KEY_BINDINGS: &str = "[ Key bindings ]";
 const QUIT: &str = "[ Save and exit ]";
 const BACK: &str = "[ Back ]";
 
@@ -40,6 +53,9 @@ enum Selection {
     Font,
     MapZoom,
     TextZoom,
+    WindowMode,
+    IntegerScale,
+    KeyBindings,
     Quit,
 }
 
@@ -81,13 +97,20 @@ impl OptionsMenuMode {
                 .unwrap_or_else(|| UNKNOWN_TILESET_NAME.len());
         let map_zoom_width = 2 + MAP_ZOOM_LABEL.len() + ZOOM_1X_ON.len() + ZOOM_2X_ON.len();
         let text_zoom_width = 2 + TEXT_ZOOM_LABEL.len() + ZOOM_1X_ON.len() + ZOOM_2X_ON.len();
+        let window_mode_width =
+            7 + WINDOW_MODE_LABEL.len() + WINDOW_MODES.iter().map(|(_, n)| n.len()).max().unwrap();
+        let integer_scale_width =
+            2 + INTEGER_SCALE_LABEL.len() + INTEGER_SCALE_OFF_ON.len() + INTEGER_SCALE_ON_ON.len();
         let new_grid_size = Size {
             w: 4 + tileset_width
                 .max(font_width)
                 .max(map_zoom_width)
                 .max(text_zoom_width)
+                .max(window_mode_width)
+                .max(integer_scale_width)
+                .max(KEY_BINDINGS.len())
                 .max(QUIT.len()) as u32,
-            h: 10,
+            h: 13,
         };
         let Options {
             font, text_zoom, ..
@@ -105,6 +128,15 @@ impl OptionsMenuMode {
         grids[0].view.zoom = text_zoom;
     }
 
+    /// Save the options on the way out so they persist between runs.
+    fn pop(world: &World, result: OptionsMenuModeResult) -> ModeControl {
+        if let Err(e) = saveload::save_options(world) {
+            eprintln!("Warning: options_menu::pop: {}", e);
+        }
+
+        ModeControl::Pop(result.into())
+    }
+
     pub fn update(
         &mut self,
         world: &World,
@@ -116,15 +148,22 @@ impl OptionsMenuMode {
             return match result {
                 ModeResult::YesNoDialogModeResult(result) => match result {
                     YesNoDialogModeResult::AppQuit => (
-                        ModeControl::Pop(OptionsMenuModeResult::AppQuit.into()),
+                        Self::pop(world, OptionsMenuModeResult::AppQuit),
                         ModeUpdate::Immediate,
                     ),
                     YesNoDialogModeResult::Yes => (
-                        ModeControl::Pop(OptionsMenuModeResult::ReallyQuit.into()),
+                        Self::pop(world, OptionsMenuModeResult::ReallyQuit),
                         ModeUpdate::Immediate,
                     ),
                     YesNoDialogModeResult::No => (ModeControl::Stay, ModeUpdate::WaitForEvent),
                 },
+                ModeResult::KeyBindingsModeResult(result) => match result {
+                    KeyBindingsModeResult::AppQuit => (
+                        Self::pop(world, OptionsMenuModeResult::AppQuit),
+                        ModeUpdate::Immediate,
+                    ),
+                    KeyBindingsModeResult::Closed => (ModeControl::Stay, ModeUpdate::WaitForEvent),
+                },
                 _ => unreachable!(),
             };
         }
@@ -133,14 +172,12 @@ impl OptionsMenuMode {
 
         if let Some(InputEvent::AppQuit) = inputs.get_input() {
             return (
-                ModeControl::Pop(OptionsMenuModeResult::AppQuit.into()),
+                Self::pop(world, OptionsMenuModeResult::AppQuit),
                 ModeUpdate::Immediate,
             );
-        } else if let Some(InputEvent::Press(keycode)) = inputs.get_input() {
+        } else if let Some(key) = gamekey::from_input(world, inputs) {
             let mut options = world.borrow::<UniqueViewMut<Options>>().unwrap();
-            let gkey = gamekey::from_keycode(keycode, inputs.get_mods(KeyMods::SHIFT));
-
-            match (&self.selection, gkey) {
+            match (&self.selection, key) {
                 (Selection::Tileset, GameKey::Up) => self.selection = Selection::Quit,
                 (Selection::Tileset, GameKey::Down) => self.selection = Selection::Font,
                 (Selection::Tileset, GameKey::Left) => {
@@ -189,7 +226,7 @@ impl OptionsMenuMode {
                 }
 
                 (Selection::TextZoom, GameKey::Up) => self.selection = Selection::MapZoom,
-                (Selection::TextZoom, GameKey::Down) => self.selection = Selection::Quit,
+                (Selection::TextZoom, GameKey::Down) => self.selection = Selection::WindowMode,
                 (Selection::TextZoom, GameKey::Left) => {
                     options.text_zoom = 1;
                     inputs.clear_input();
@@ -201,7 +238,53 @@ impl OptionsMenuMode {
                     return (ModeControl::Stay, ModeUpdate::Immediate);
                 }
 
-                (Selection::Quit, GameKey::Up) => self.selection = Selection::TextZoom,
+                (Selection::WindowMode, GameKey::Up) => self.selection = Selection::TextZoom,
+                (Selection::WindowMode, GameKey::Down) => self.selection = Selection::IntegerScale,
+                (Selection::WindowMode, GameKey::Left) => {
+                    let index = Self::window_mode_index(options.window_mode);
+                    if index > 0 {
+                        options.window_mode = WINDOW_MODES[index - 1].0;
+                        inputs.request_window_mode(options.window_mode);
+                        inputs.clear_input();
+                        return (ModeControl::Stay, ModeUpdate::Immediate);
+                    }
+                }
+                (Selection::WindowMode, GameKey::Right) => {
+                    let index = Self::window_mode_index(options.window_mode);
+                    if index + 1 < WINDOW_MODES.len() {
+                        options.window_mode = WINDOW_MODES[index + 1].0;
+                        inputs.request_window_mode(options.window_mode);
+                        inputs.clear_input();
+                        return (ModeControl::Stay, ModeUpdate::Immediate);
+                    }
+                }
+
+                (Selection::IntegerScale, GameKey::Up) => self.selection = Selection::WindowMode,
+                (Selection::IntegerScale, GameKey::Down) => self.selection = Selection::KeyBindings,
+                (Selection::IntegerScale, GameKey::Left) => {
+                    options.integer_scale = false;
+                    inputs.request_integer_scale(false);
+                    inputs.clear_input();
+                    return (ModeControl::Stay, ModeUpdate::Immediate);
+                }
+                (Selection::IntegerScale, GameKey::Right) => {
+                    options.integer_scale = true;
+                    inputs.request_integer_scale(true);
+                    inputs.clear_input();
+                    return (ModeControl::Stay, ModeUpdate::Immediate);
+                }
+
+                (Selection::KeyBindings, GameKey::Up) => self.selection = Selection::IntegerScale,
+                (Selection::KeyBindings, GameKey::Down) => self.selection = Selection::Quit,
+                (Selection::KeyBindings, GameKey::Confirm) => {
+                    inputs.clear_input();
+                    return (
+                        ModeControl::Push(KeyBindingsMode::new().into()),
+                        ModeUpdate::Immediate,
+                    );
+                }
+
+                (Selection::Quit, GameKey::Up) => self.selection = Selection::KeyBindings,
                 (Selection::Quit, GameKey::Down) => self.selection = Selection::Tileset,
                 (Selection::Quit, GameKey::Confirm) => {
                     inputs.clear_input();
@@ -215,7 +298,7 @@ impl OptionsMenuMode {
                                 .into(),
                             )
                         } else {
-                            ModeControl::Pop(OptionsMenuModeResult::Closed.into())
+                            Self::pop(world, OptionsMenuModeResult::Closed)
                         },
                         ModeUpdate::Immediate,
                     );
@@ -223,7 +306,7 @@ impl OptionsMenuMode {
 
                 (_, GameKey::Cancel) => {
                     return (
-                        ModeControl::Pop(OptionsMenuModeResult::Closed.into()),
+                        Self::pop(world, OptionsMenuModeResult::Closed),
                         ModeUpdate::Immediate,
                     )
                 }
@@ -410,6 +493,97 @@ impl OptionsMenuMode {
         );
     }
 
+    fn window_mode_index(window_mode: WindowMode) -> usize {
+        WINDOW_MODES
+            .iter()
+            .position(|(mode, _)| *mode == window_mode)
+            .unwrap()
+    }
+
+    fn draw_window_mode(
+        &self,
+        world: &World,
+        grid: &mut TileGrid<GameSym>,
+        fg: Color,
+        bg: Color,
+        selected_bg: Color,
+    ) {
+        let window_mode_left_x = 3 + WINDOW_MODE_LABEL.len() as i32;
+        let window_mode_name_x = 3 + window_mode_left_x;
+        let window_mode_right_x = 1
+            + window_mode_name_x
+            + WINDOW_MODES.iter().map(|(_, n)| n.len()).max().unwrap() as i32;
+        let window_mode_y = 6;
+        let window_mode = world.borrow::<UniqueView<Options>>().unwrap().window_mode;
+        let index = Self::window_mode_index(window_mode);
+
+        grid.print((2, window_mode_y), WINDOW_MODE_LABEL);
+        if index > 0 {
+            grid.print_color((window_mode_left_x, window_mode_y), "<<", true, fg, bg);
+        }
+        grid.print_color(
+            (window_mode_name_x, window_mode_y),
+            WINDOW_MODES[index].1,
+            true,
+            fg,
+            if matches!(self.selection, Selection::WindowMode) {
+                selected_bg
+            } else {
+                bg
+            },
+        );
+        if index + 1 < WINDOW_MODES.len() {
+            grid.print_color((window_mode_right_x, window_mode_y), ">>", true, fg, bg);
+        }
+    }
+
+    fn draw_integer_scale(
+        &self,
+        world: &World,
+        grid: &mut TileGrid<GameSym>,
+        fg: Color,
+        bg: Color,
+        selected_bg: Color,
+    ) {
+        let integer_scale_off_x = 3 + INTEGER_SCALE_LABEL.len() as i32;
+        let integer_scale_on_x =
+            4 + (INTEGER_SCALE_LABEL.len() + INTEGER_SCALE_OFF_OFF.len()) as i32;
+        let integer_scale_y = 7;
+        let integer_scale = world.borrow::<UniqueView<Options>>().unwrap().integer_scale;
+
+        grid.print((2, integer_scale_y), INTEGER_SCALE_LABEL);
+        grid.print_color(
+            (integer_scale_off_x, integer_scale_y),
+            if integer_scale {
+                INTEGER_SCALE_OFF_OFF
+            } else {
+                INTEGER_SCALE_OFF_ON
+            },
+            true,
+            fg,
+            if !integer_scale && matches!(self.selection, Selection::IntegerScale) {
+                selected_bg
+            } else {
+                bg
+            },
+        );
+        grid.print_color(
+            (integer_scale_on_x, integer_scale_y),
+            if integer_scale {
+                INTEGER_SCALE_ON_ON
+            } else {
+                INTEGER_SCALE_ON_OFF
+            },
+            true,
+            fg,
+            if integer_scale && matches!(self.selection, Selection::IntegerScale) {
+                selected_bg
+            } else {
+                bg
+            },
+        );
+    }
+
     pub fn draw(&self, world: &World, grids: &mut [TileGrid<GameSym>], active: bool) {
         let grid = &mut grids[0];
         let fg = Color::WHITE;
@@ -425,9 +599,22 @@ impl OptionsMenuMode {
         self.draw_font(world, grid, fg, bg, selected_bg);
         self.draw_map_zoom(world, grid, fg, bg, selected_bg);
         self.draw_text_zoom(world, grid, fg, bg, selected_bg);
+        self.draw_window_mode(world, grid, fg, bg, selected_bg);
+        self.draw_integer_scale(world, grid, fg, bg, selected_bg);
 
         grid.print_color(
-            (2, 7),
+            (2, 9),
+            KEY_BINDINGS,
+            true,
+            fg,
+            if matches!(self.selection, Selection::KeyBindings) {
+                selected_bg
+            } else {
+                bg
+            },
+        );
+        grid.print_color(
+            (2, 10),
             if self.prompt_to_save { QUIT } else { BACK },
             true,
             fg,
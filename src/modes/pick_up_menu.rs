@@ -13,7 +13,7 @@ use crate::{
 };
 use ruggrogue::{
     util::{Color, Size},
-    InputBuffer, InputEvent, KeyMods, TileGrid, Tileset,
+    InputBuffer, InputEvent, TileGrid, Tileset,
 };
 
 use super::{ModeControl, ModeResult, ModeUpdate};
@@ -139,8 +139,8 @@ impl PickUpMenuMode {
                     ModeControl::Pop(PickUpMenuModeResult::AppQuit.into()),
                     ModeUpdate::Immediate,
                 );
-            } else if let Some(InputEvent::Press(keycode)) = inputs.get_input() {
-                match gamekey::from_keycode(keycode, inputs.get_mods(KeyMods::SHIFT)) {
+            } else if let Some(key) = gamekey::from_input(world, inputs) {
+                match key {
                     GameKey::Down => match self.subsection {
                         SubSection::Items => {
                             if self.selection < self.items.len() as i32 - 1 {
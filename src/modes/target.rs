@@ -9,10 +9,11 @@ use crate::{
     map::Map,
     player::PlayerId,
     render, ui,
+    vision::LightMap,
 };
 use ruggrogue::{
     util::{Color, Position, Size},
-    InputBuffer, InputEvent, KeyMods, TileGrid, Tileset,
+    InputBuffer, InputEvent, TileGrid, Tileset,
 };
 
 use super::{
@@ -20,6 +21,13 @@ use super::{
     ModeControl, ModeResult, ModeUpdate,
 };
 
+/// Background color of the tiles that a projectile would pass through on its way to the cursor.
+const PATH_BG: Color = Color {
+    r: 0,
+    g: 96,
+    b: 160,
+};
+
 pub enum TargetModeResult {
     AppQuit,
     Cancelled,
@@ -36,6 +44,7 @@ pub struct TargetMode {
     radius: i32,
     valid: HashSet<(i32, i32)>,
     cursor: (i32, i32), // x, y
+    path: Vec<(i32, i32)>,
     warn_self: bool,
 }
 
@@ -43,7 +52,14 @@ fn dist2((x1, y1): (i32, i32), (x2, y2): (i32, i32)) -> i32 {
     (x2 - x1).pow(2) + (y2 - y1).pow(2)
 }
 
-/// Pick a target position within a certain range of the player.
+/// The tiles a projectile fired from `center` would pass through on its way to `cursor`.
+fn trace_path(world: &World, center: (i32, i32), cursor: (i32, i32)) -> Vec<(i32, i32)> {
+    let map = world.borrow::<UniqueView<Map>>().unwrap();
+
+    ruggrogue::line_of_fire(&*map, center, cursor).collect()
+}
+
+/// Pick a target position within a certain range of the player with a clear line of fire to it.
 impl TargetMode {
     pub fn new(world: &World, for_what: String, range: i32, radius: i32, warn_self: bool) -> Self {
         assert!(range >= 0);
@@ -61,22 +77,30 @@ impl TargetMode {
                 .unwrap()
                 .iter()
                 .filter(|pos| dist2(*pos, player_pos) <= max_dist2)
+                .filter(|pos| {
+                    *pos == player_pos || trace_path(world, player_pos, *pos).last() == Some(pos)
+                })
                 .collect::<HashSet<_>>()
         });
 
-        // Default to the closest monster position, or the player if no monsters are present.
+        // Default to the closest visible monster position, or the player if no monsters are present.
         let cursor = valid
             .iter()
             .filter(|(x, y)| {
                 world
-                    .borrow::<UniqueView<Map>>()
+                    .borrow::<UniqueView<LightMap>>()
                     .unwrap()
-                    .iter_entities_at(*x, *y)
-                    .any(|id| world.borrow::<View<Monster>>().unwrap().contains(id))
+                    .is_lit((*x, *y))
+                    && world
+                        .borrow::<UniqueView<Map>>()
+                        .unwrap()
+                        .iter_entities_at(*x, *y)
+                        .any(|id| world.borrow::<View<Monster>>().unwrap().contains(id))
             })
             .min_by_key(|pos| dist2(**pos, player_pos))
             .copied()
             .unwrap_or(player_pos);
+        let path = trace_path(world, player_pos, cursor);
 
         Self {
             chunked_map_grid: ChunkedMapGrid::new(),
@@ -88,6 +112,7 @@ impl TargetMode {
             radius,
             valid,
             cursor,
+            path,
             warn_self,
         }
     }
@@ -116,7 +141,7 @@ impl TargetMode {
 
     pub fn update(
         &mut self,
-        _world: &World,
+        world: &World,
         inputs: &mut InputBuffer,
         _grids: &[TileGrid<GameSym>],
         pop_result: &Option<ModeResult>,
@@ -151,14 +176,14 @@ impl TargetMode {
                 ModeControl::Pop(TargetModeResult::AppQuit.into()),
                 ModeUpdate::Immediate,
             );
-        } else if let Some(InputEvent::Press(keycode)) = inputs.get_input() {
+        } else if let Some(key) = gamekey::from_input(world, inputs) {
             let min_x = self.center.0 - self.range;
             let max_x = self.center.0 + self.range;
             let min_y = self.center.1 - self.range;
             let max_y = self.center.1 + self.range;
             let old_cursor = self.cursor;
 
-            match gamekey::from_keycode(keycode, inputs.get_mods(KeyMods::SHIFT)) {
+            match key {
                 GameKey::Left => {
                     self.cursor.0 = std::cmp::max(min_x, self.cursor.0 - 1);
                 }
@@ -238,6 +263,8 @@ impl TargetMode {
             }
 
             if self.cursor != old_cursor {
+                self.path = trace_path(world, self.center, self.cursor);
+
                 // Moving the cursor is the only reason to redraw right now.
                 self.chunked_map_grid.mark_dirty(
                     Position {
@@ -295,6 +322,16 @@ impl TargetMode {
             }
         }
 
+        // Highlight the path of the projectile.
+        for &(x, y) in &self.path {
+            if let Some(pos) = self
+                .chunked_map_grid
+                .map_to_grid_pos(world, Position { x, y })
+            {
+                map_grid.recolor_pos(pos, None, PATH_BG);
+            }
+        }
+
         // Highlight area of effect.
         for y in (self.cursor.1 - self.radius)..=(self.cursor.1 + self.radius) {
             for x in (self.cursor.0 - self.radius)..=(self.cursor.0 + self.radius) {
@@ -324,6 +361,8 @@ impl TargetMode {
                 .unwrap()
                 .describe_pos(world, self.cursor.0, self.cursor.1, true, false, false)
                 .0
+        } else if self.cursor != self.center && self.path.last() != Some(&self.cursor) {
+            "No clear line of fire".to_string()
         } else {
             "Out of range".to_string()
         };
@@ -0,0 +1,163 @@
+use sdl2::keyboard::Keycode;
+use shipyard::{UniqueView, World};
+
+use crate::{
+    gamesym::GameSym,
+    ui::{self, Options},
+};
+use ruggrogue::{
+    util::{Color, Size},
+    InputBuffer, InputEvent, TileGrid, Tileset,
+};
+
+use super::{ModeControl, ModeResult, ModeUpdate};
+
+pub enum TextEntryModeResult {
+    AppQuit,
+    Cancelled,
+    Entered(String),
+}
+
+pub struct TextEntryMode {
+    prompt: String,
+    text: Vec<char>,
+    cursor: usize,
+    max_length: usize,
+}
+
+/// A dialog box with a prompt and a single-line text field that shows up in the center of the
+/// screen.  Typed text is inserted at the cursor, which can be moved with the arrow keys, Home and
+/// End; Backspace and Delete remove characters, Enter confirms and Escape cancels.
+impl TextEntryMode {
+    pub fn new(prompt: String, initial: &str, max_length: usize) -> Self {
+        assert!(max_length > 0);
+
+        let text: Vec<char> = initial.chars().take(max_length).collect();
+        let cursor = text.len();
+
+        Self {
+            prompt,
+            text,
+            cursor,
+            max_length,
+        }
+    }
+
+    pub fn prepare_grids(
+        &self,
+        world: &World,
+        grids: &mut Vec<TileGrid<GameSym>>,
+        tilesets: &[Tileset<GameSym>],
+        window_size: Size,
+    ) {
+        let Options {
+            font, text_zoom, ..
+        } = *world.borrow::<UniqueView<Options>>().unwrap();
+        let new_grid_size = Size {
+            w: 4 + self.prompt.chars().count().max(self.max_length + 1) as u32,
+            h: 7,
+        };
+
+        if !grids.is_empty() {
+            grids[0].resize(new_grid_size);
+        } else {
+            grids.push(TileGrid::new(new_grid_size, tilesets, font as usize));
+            grids[0].view.clear_color = None;
+        }
+
+        grids[0].set_tileset(tilesets, font as usize);
+        grids[0].view_centered(tilesets, text_zoom, (0, 0).into(), window_size);
+        grids[0].view.zoom = text_zoom;
+    }
+
+    pub fn update(
+        &mut self,
+        _world: &World,
+        inputs: &mut InputBuffer,
+        _grids: &[TileGrid<GameSym>],
+        _pop_result: &Option<ModeResult>,
+    ) -> (ModeControl, ModeUpdate) {
+        inputs.start_text_input();
+        inputs.prepare_input();
+
+        match inputs.get_input() {
+            Some(InputEvent::AppQuit) => {
+                inputs.stop_text_input();
+                return (
+                    ModeControl::Pop(TextEntryModeResult::AppQuit.into()),
+                    ModeUpdate::Immediate,
+                );
+            }
+
+            Some(InputEvent::Text(text)) => {
+                for ch in text.chars().filter(|ch| !ch.is_control()) {
+                    if self.text.len() < self.max_length {
+                        self.text.insert(self.cursor, ch);
+                        self.cursor += 1;
+                    }
+                }
+            }
+
+            Some(InputEvent::Press(keycode)) => match keycode {
+                Keycode::Left => self.cursor = self.cursor.saturating_sub(1),
+                Keycode::Right => self.cursor = (self.cursor + 1).min(self.text.len()),
+                Keycode::Home => self.cursor = 0,
+                Keycode::End => self.cursor = self.text.len(),
+                Keycode::Backspace if self.cursor > 0 => {
+                    self.cursor -= 1;
+                    self.text.remove(self.cursor);
+                }
+                Keycode::Delete if self.cursor < self.text.len() => {
+                    self.text.remove(self.cursor);
+                }
+                Keycode::Return | Keycode::KpEnter => {
+                    inputs.stop_text_input();
+                    inputs.clear_input();
+                    return (
+                        ModeControl::Pop(
+                            TextEntryModeResult::Entered(self.text.iter().collect()).into(),
+                        ),
+                        ModeUpdate::Immediate,
+                    );
+                }
+                Keycode::Escape => {
+                    inputs.stop_text_input();
+                    inputs.clear_input();
+                    return (
+                        ModeControl::Pop(TextEntryModeResult::Cancelled.into()),
+                        ModeUpdate::Immediate,
+                    );
+                }
+                _ => {}
+            },
+
+            _ => {}
+        }
+
+        (ModeControl::Stay, ModeUpdate::WaitForEvent)
+    }
+
+    pub fn draw(&self, _world: &World, grids: &mut [TileGrid<GameSym>], active: bool) {
+        let grid = &mut grids[0];
+        let fg = Color::WHITE;
+        let bg = Color::BLACK;
+        let field_bg = Color::DARK_GRAY;
+
+        grid.view.color_mod = if active { Color::WHITE } else { Color::GRAY };
+
+        grid.draw_box((0, 0), (grid.width(), grid.height()), fg, bg);
+        grid.print((2, 2), &self.prompt);
+
+        // Draw the text field with one extra cell at the end to show the cursor.
+        for x in 0..=self.max_length {
+            let ch = self.text.get(x).copied().unwrap_or(' ');
+            let cell_bg = if active && x == self.cursor {
+                ui::SELECTED_BG
+            } else {
+                field_bg
+            };
+
+            grid.put_char_color((2 + x as i32, 4), ch, fg, cell_bg);
+        }
+    }
+}
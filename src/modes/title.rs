@@ -1,7 +1,8 @@
 use shipyard::{AllStoragesViewMut, Get, UniqueView, UniqueViewMut, View, ViewMut, World};
 
 use crate::{
-    components::{CombatStats, Experience, FieldOfView},
+    components::{CombatStats, Experience, FieldOfView, Name},
+    effects::Effects,
     experience::{self, Difficulty},
     gamekey::{self, GameKey},
     gamesym::GameSym,
@@ -10,20 +11,22 @@ use crate::{
     menu_memory::MenuMemory,
     message::Messages,
     player::{self, PlayerAlive, PlayerId},
-    saveload, spawn,
+    saveload::{self, SaveSlot},
+    spawn,
     ui::{self, Options},
     vision, BaseEquipmentLevel, GameSeed, TurnCount, Wins,
 };
 use ruggrogue::{
     util::{Color, Size},
-    InputBuffer, InputEvent, KeyMods, TileGrid, Tileset,
+    InputBuffer, InputEvent, TileGrid, Tileset,
 };
 
 use super::{
     dungeon::DungeonMode,
+    load_game::{LoadGameMode, LoadGameModeResult},
     message_box::{MessageBoxMode, MessageBoxModeResult},
     options_menu::{OptionsMenuMode, OptionsMenuModeResult},
-    yes_no_dialog::{YesNoDialogMode, YesNoDialogModeResult},
+    text_entry::{TextEntryMode, TextEntryModeResult},
     ModeControl, ModeResult, ModeUpdate,
 };
 
@@ -32,6 +35,8 @@ const VERSION_GRID: usize = 1;
 const SOURCE_GRID: usize = 2;
 const MENU_GRID: usize = 3;
 
+const MAX_NAME_LEN: usize = 14;
+
 const SOURCE_STR: &str = "tung.github.io/ruggrogue/";
 const VERSION_STR: &str = "v1.0.1";
 const LOGO_STR: &str = "░░░░░░  ░░  ░░   ░░░░    ░░░░
@@ -88,6 +93,7 @@ fn print_game_seed(game_seed: UniqueView<GameSeed>) {
 pub fn new_game_setup(world: &World, new_game_plus: bool) {
     world.borrow::<UniqueViewMut<MenuMemory>>().unwrap().reset();
     world.borrow::<UniqueViewMut<Messages>>().unwrap().reset();
+    world.borrow::<UniqueViewMut<Effects>>().unwrap().clear();
     world.borrow::<UniqueViewMut<Map>>().unwrap().clear();
     world.borrow::<UniqueViewMut<PlayerAlive>>().unwrap().0 = true;
 
@@ -204,9 +210,10 @@ impl TitleMode {
         let mut actions = vec![TitleAction::NewGame];
 
         // There's no obvious way to get Emscripten to load the IndexedDB filesystem in time to
-        // realize that a save file exists, so always include the Load Game option for it and just
-        // check if there really is a save file when the option is chosen instead.
-        if cfg!(target_os = "emscripten") || saveload::save_file_exists() {
+        // realize that save files exist, so always include the Load Game option for it and just
+        // check if there really are save files when the option is chosen instead.
+        let save_slots_exist = saveload::save_slots_exist();
+        if cfg!(target_os = "emscripten") || save_slots_exist {
             actions.push(TitleAction::LoadGame);
         }
 
@@ -215,7 +222,7 @@ impl TitleMode {
         #[cfg(not(target_arch = "wasm32"))]
         actions.push(TitleAction::Quit);
 
-        let selection = if saveload::save_file_exists() {
+        let selection = if save_slots_exist {
             actions
                 .iter()
                 .position(|a| matches!(*a, TitleAction::LoadGame))
@@ -342,6 +349,49 @@ impl TitleMode {
     ) -> (ModeControl, ModeUpdate) {
         if let Some(result) = pop_result {
             return match result {
+                ModeResult::LoadGameModeResult(result) => match result {
+                    LoadGameModeResult::AppQuit => (
+                        ModeControl::Pop(TitleModeResult::AppQuit.into()),
+                        ModeUpdate::Immediate,
+                    ),
+                    LoadGameModeResult::Cancelled => {
+                        if !cfg!(target_os = "emscripten") && !saveload::save_slots_exist() {
+                            // Remove the load game option.
+                            self.actions
+                                .retain(|a| !matches!(*a, TitleAction::LoadGame));
+
+                            // Adjust selection if needed.
+                            if let Some(pos) = self
+                                .actions
+                                .iter()
+                                .position(|a| matches!(*a, TitleAction::NewGame))
+                            {
+                                self.selection = pos;
+                            } else {
+                                self.selection =
+                                    self.selection.min(self.actions.len().saturating_sub(1));
+                            }
+                        }
+
+                        inputs.clear_input();
+                        (ModeControl::Stay, ModeUpdate::Immediate)
+                    }
+                    LoadGameModeResult::Loaded => {
+                        world.run(print_game_seed);
+
+                        // Light levels aren't saved, so calculate them for the loaded map.
+                        world.run(vision::recalculate_fields_of_view);
+
+                        // Don't show pick up key hint to returning players.
+                        world.borrow::<UniqueViewMut<PickUpHint>>().unwrap().0 = false;
+
+                        inputs.clear_input();
+                        (
+                            ModeControl::Switch(DungeonMode::new().into()),
+                            ModeUpdate::Immediate,
+                        )
+                    }
+                },
                 ModeResult::MessageBoxModeResult(result) => match result {
                     MessageBoxModeResult::AppQuit => (
                         ModeControl::Pop(TitleModeResult::AppQuit.into()),
@@ -357,34 +407,35 @@ impl TitleMode {
                     OptionsMenuModeResult::Closed => (ModeControl::Stay, ModeUpdate::WaitForEvent),
                     OptionsMenuModeResult::ReallyQuit => unreachable!(),
                 },
-                ModeResult::YesNoDialogModeResult(result) => match result {
-                    YesNoDialogModeResult::AppQuit => (
+                ModeResult::TextEntryModeResult(result) => match result {
+                    TextEntryModeResult::AppQuit => (
                         ModeControl::Pop(TitleModeResult::AppQuit.into()),
                         ModeUpdate::Immediate,
                     ),
-                    YesNoDialogModeResult::Yes => {
-                        saveload::delete_save_file();
-
-                        // Remove the load game option.
-                        self.actions
-                            .retain(|a| !matches!(*a, TitleAction::LoadGame));
-
-                        // Adjust selection if needed.
-                        if let Some(pos) = self
-                            .actions
-                            .iter()
-                            .position(|a| matches!(*a, TitleAction::NewGame))
+                    TextEntryModeResult::Cancelled => (ModeControl::Stay, ModeUpdate::WaitForEvent),
+                    TextEntryModeResult::Entered(name) => {
+                        new_game_setup(world, false);
+
+                        // Name the fresh player as requested and give them their own save slot.
+                        let player_id = world.borrow::<UniqueView<PlayerId>>().unwrap().0;
+                        if let Ok(player_name) =
+                            (&mut world.borrow::<ViewMut<Name>>().unwrap()).get(player_id)
                         {
-                            self.selection = pos;
-                        } else {
-                            self.selection =
-                                self.selection.min(self.actions.len().saturating_sub(1));
+                            let name = name.trim();
+                            if !name.is_empty() {
+                                player_name.0 = name.to_string();
+                            }
+
+                            world.borrow::<UniqueViewMut<SaveSlot>>().unwrap().0 =
+                                saveload::new_slot_name(&player_name.0);
                         }
 
                         inputs.clear_input();
-                        (ModeControl::Stay, ModeUpdate::Immediate)
+                        (
+                            ModeControl::Switch(DungeonMode::new().into()),
+                            ModeUpdate::Immediate,
+                        )
                     }
-                    YesNoDialogModeResult::No => (ModeControl::Stay, ModeUpdate::WaitForEvent),
                 },
                 _ => unreachable!(),
             };
@@ -392,134 +443,83 @@ impl TitleMode {
 
         inputs.prepare_input();
 
-        match inputs.get_input() {
-            Some(InputEvent::AppQuit) => {
-                return (
-                    ModeControl::Pop(TitleModeResult::AppQuit.into()),
-                    ModeUpdate::Immediate,
-                );
-            }
-
-            Some(InputEvent::Press(keycode)) => {
-                match gamekey::from_keycode(keycode, inputs.get_mods(KeyMods::SHIFT)) {
-                    GameKey::Up => {
-                        if self.selection > 0 {
-                            self.selection -= 1;
-                        } else {
-                            self.selection = self.actions.len().saturating_sub(1);
-                        }
+        if let Some(InputEvent::AppQuit) = inputs.get_input() {
+            return (
+                ModeControl::Pop(TitleModeResult::AppQuit.into()),
+                ModeUpdate::Immediate,
+            );
+        } else if let Some(key) = gamekey::from_input(world, inputs) {
+            match key {
+                GameKey::Up => {
+                    if self.selection > 0 {
+                        self.selection -= 1;
+                    } else {
+                        self.selection = self.actions.len().saturating_sub(1);
                     }
-                    GameKey::Down => {
-                        if self.selection < self.actions.len().saturating_sub(1) {
-                            self.selection += 1;
-                        } else {
-                            self.selection = 0;
-                        }
+                }
+                GameKey::Down => {
+                    if self.selection < self.actions.len().saturating_sub(1) {
+                        self.selection += 1;
+                    } else {
+                        self.selection = 0;
                     }
-                    GameKey::Cancel => {
-                        if let Some(quit_pos) = self
-                            .actions
-                            .iter()
-                            .position(|a| matches!(*a, TitleAction::Quit))
-                        {
-                            self.selection = quit_pos;
-                        }
+                }
+                GameKey::Cancel => {
+                    if let Some(quit_pos) = self
+                        .actions
+                        .iter()
+                        .position(|a| matches!(*a, TitleAction::Quit))
+                    {
+                        self.selection = quit_pos;
                     }
-                    GameKey::Confirm => {
-                        assert!(self.selection < self.actions.len());
-
-                        match self.actions[self.selection] {
-                            TitleAction::NewGame => {
-                                if saveload::save_file_exists() {
-                                    inputs.clear_input();
-                                    return (
-                                        ModeControl::Push(
-                                            YesNoDialogMode::new(
-                                                "Save data already exists.  Delete it?".into(),
-                                                false,
-                                            )
-                                            .into(),
-                                        ),
-                                        ModeUpdate::Immediate,
-                                    );
-                                } else {
-                                    new_game_setup(world, false);
-                                    inputs.clear_input();
-                                    return (
-                                        ModeControl::Switch(DungeonMode::new().into()),
-                                        ModeUpdate::Immediate,
-                                    );
-                                }
-                            }
-                            TitleAction::LoadGame => {
-                                if saveload::save_file_exists() {
-                                    match saveload::load_game(world) {
-                                        Ok(_) => {
-                                            world.run(print_game_seed);
-
-                                            // Don't show pick up key hint to returning players.
-                                            world
-                                                .borrow::<UniqueViewMut<PickUpHint>>()
-                                                .unwrap()
-                                                .0 = false;
-
-                                            inputs.clear_input();
-                                            return (
-                                                ModeControl::Switch(DungeonMode::new().into()),
-                                                ModeUpdate::Immediate,
-                                            );
-                                        }
-                                        Err(e) => {
-                                            let mut msg = vec![
-                                                "Failed to load game:".to_string(),
-                                                "".to_string(),
-                                            ];
-
-                                            msg.extend(
-                                                ruggrogue::word_wrap(&format!("{}", e), 78)
-                                                    .map(String::from),
-                                            );
-
-                                            inputs.clear_input();
-                                            return (
-                                                ModeControl::Push(MessageBoxMode::new(msg).into()),
-                                                ModeUpdate::Immediate,
-                                            );
-                                        }
-                                    }
+                }
+                GameKey::Confirm => {
+                    assert!(self.selection < self.actions.len());
+
+                    match self.actions[self.selection] {
+                        TitleAction::NewGame => {
+                            inputs.clear_input();
+                            return (
+                                ModeControl::Push(
+                                    TextEntryMode::new(
+                                        "What is your name?".into(),
+                                        "Player",
+                                        MAX_NAME_LEN,
+                                    )
+                                    .into(),
+                                ),
+                                ModeUpdate::Immediate,
+                            );
+                        }
+                        TitleAction::LoadGame => {
+                            inputs.clear_input();
+                            return (
+                                ModeControl::Push(if saveload::save_slots_exist() {
+                                    LoadGameMode::new().into()
                                 } else {
-                                    inputs.clear_input();
-                                    return (
-                                        ModeControl::Push(
-                                            MessageBoxMode::new(vec![
-                                                "No save file found.".to_string()
-                                            ])
-                                            .into(),
-                                        ),
-                                        ModeUpdate::Immediate,
-                                    );
-                                }
-                            }
-                            TitleAction::Options => {
-                                inputs.clear_input();
-                                return (
-                                    ModeControl::Push(OptionsMenuMode::new(false).into()),
-                                    ModeUpdate::Immediate,
-                                );
-                            }
-                            TitleAction::Quit => {
-                                return (
-                                    ModeControl::Pop(TitleModeResult::AppQuit.into()),
-                                    ModeUpdate::Immediate,
-                                );
-                            }
+                                    MessageBoxMode::new(vec!["No save file found.".to_string()])
+                                        .into()
+                                }),
+                                ModeUpdate::Immediate,
+                            );
+                        }
+                        TitleAction::Options => {
+                            inputs.clear_input();
+                            return (
+                                ModeControl::Push(OptionsMenuMode::new(false).into()),
+                                ModeUpdate::Immediate,
+                            );
+                        }
+                        TitleAction::Quit => {
+                            return (
+                                ModeControl::Pop(TitleModeResult::AppQuit.into()),
+                                ModeUpdate::Immediate,
+                            );
                         }
                     }
-                    _ => {}
                 }
+                _ => {}
             }
-
-            _ => {}
         }
 
         (ModeControl::Stay, ModeUpdate::WaitForEvent)
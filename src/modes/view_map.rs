@@ -89,13 +89,13 @@ impl ViewMapMode {
                 ModeControl::Pop(ViewMapModeResult::AppQuit.into()),
                 ModeUpdate::Immediate,
             );
-        } else if let Some(InputEvent::Press(keycode)) = inputs.get_input() {
+        } else if let Some(key) = gamekey::from_input(world, inputs) {
             let shift = inputs.get_mods(KeyMods::SHIFT);
             let move_amount = if shift { SHIFT_STEP } else { 1 };
             let mut move_x = 0;
             let mut move_y = 0;
 
-            match gamekey::from_keycode(keycode, shift) {
+            match key {
                 GameKey::Up => move_y = -move_amount,
                 GameKey::Down => move_y = move_amount,
                 GameKey::Left => move_x = -move_amount,
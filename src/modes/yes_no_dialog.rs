@@ -7,7 +7,7 @@ use crate::{
 };
 use ruggrogue::{
     util::{Color, Size},
-    InputBuffer, InputEvent, KeyMods, TileGrid, Tileset,
+    InputBuffer, InputEvent, TileGrid, Tileset,
 };
 
 use super::{ModeControl, ModeResult, ModeUpdate};
@@ -74,7 +74,7 @@ impl YesNoDialogMode {
 
     pub fn update(
         &mut self,
-        _world: &World,
+        world: &World,
         inputs: &mut InputBuffer,
         _grids: &[TileGrid<GameSym>],
         _pop_result: &Option<ModeResult>,
@@ -86,8 +86,8 @@ impl YesNoDialogMode {
                 ModeControl::Pop(YesNoDialogModeResult::AppQuit.into()),
                 ModeUpdate::Immediate,
             );
-        } else if let Some(InputEvent::Press(keycode)) = inputs.get_input() {
-            match gamekey::from_keycode(keycode, inputs.get_mods(KeyMods::SHIFT)) {
+        } else if let Some(key) = gamekey::from_input(world, inputs) {
+            match key {
                 GameKey::Left => self.yes_selected = true,
                 GameKey::Right => self.yes_selected = false,
                 GameKey::Confirm => {
@@ -10,6 +10,7 @@ use crate::{
     map::Map,
     player::{self, PlayerId},
 };
+use ruggrogue::Neighborhood;
 
 #[derive(Unique)]
 pub struct MonsterTurns(BinaryHeap<(Reverse<i32>, EntityId)>);
@@ -56,7 +57,9 @@ fn do_turn_for_one_monster(world: &World, monster: EntityId) {
             )
         };
 
-        if let Some(step) = ruggrogue::find_path(&*map, pos, player_pos, 4, true).nth(1) {
+        if let Some(step) =
+            ruggrogue::find_path(&*map, pos, player_pos, Neighborhood::Eight, 4, true).nth(1)
+        {
             if step == player_pos {
                 world.run_with_data(damage::melee_attack, (monster, player_id.0));
             } else {
@@ -1,4 +1,4 @@
-use sdl2::keyboard::Keycode;
+use sdl2::{controller::Button, keyboard::Keycode};
 use serde::{Deserialize, Serialize};
 use shipyard::{
     EntitiesView, EntityId, Get, IntoIter, IntoWithId, Remove, Unique, UniqueView, UniqueViewMut,
@@ -7,13 +7,16 @@ use shipyard::{
 
 use crate::{
     components::{CombatStats, Coord, FieldOfView, Item, Monster, Name, Player},
-    damage, experience,
+    damage,
+    effects::Effects,
+    experience,
     gamekey::{self, GameKey},
     hunger::{self, CanRegenResult},
     item::{self, PickUpHint},
     map::{self, Map, Tile},
     message::Messages,
-    spawn, vision,
+    spawn,
+    vision::{self, LightMap},
 };
 use ruggrogue::{util::Position, InputBuffer, InputEvent, KeyMods, PathableMap};
 
@@ -65,6 +68,7 @@ pub fn player_stop_auto_run(player_id: UniqueView<PlayerId>, mut players: ViewMu
 }
 
 pub fn player_sees_foes(
+    light_map: UniqueView<LightMap>,
     map: UniqueView<Map>,
     player_id: UniqueView<PlayerId>,
     fovs: View<FieldOfView>,
@@ -73,6 +77,7 @@ pub fn player_sees_foes(
     fovs.get(player_id.0)
         .unwrap()
         .iter()
+        .filter(|pos| light_map.is_lit(*pos))
         .any(|(x, y)| map.iter_entities_at(x, y).any(|id| monsters.contains(id)))
 }
 
@@ -699,6 +704,7 @@ pub fn player_do_descend(world: &World) {
         map.clear();
         map.depth += 1;
     });
+    world.borrow::<UniqueViewMut<Effects>>().unwrap().clear();
     if let Some(victory_pos) = world.run(map::generate_rooms_and_corridors) {
         world.run_with_data(spawn::spawn_present, victory_pos);
     }
@@ -804,10 +810,7 @@ pub fn player_input(world: &World, inputs: &mut InputBuffer) -> PlayerInputResul
     if item::is_asleep(world, player_id.0) {
         if let Some(InputEvent::AppQuit) = inputs.get_input() {
             PlayerInputResult::AppQuit
-        } else if let Some(InputEvent::Press(keycode)) = inputs.get_input() {
-            let shift = inputs.get_mods(KeyMods::SHIFT);
-            let key = gamekey::from_keycode(keycode, shift);
-
+        } else if let Some(key) = gamekey::from_input(world, inputs) {
             if !matches!(key, GameKey::Unmapped) {
                 world
                     .borrow::<UniqueViewMut<Messages>>()
@@ -835,7 +838,7 @@ pub fn player_input(world: &World, inputs: &mut InputBuffer) -> PlayerInputResul
         if matches!(inputs.get_input(), Some(InputEvent::AppQuit)) {
             world.run(player_stop_auto_run);
             PlayerInputResult::AppQuit
-        } else if matches!(inputs.get_input(), Some(InputEvent::Press(_)))
+        } else if gamekey::from_input(world, inputs).is_some()
             || world.run(player_check_frontier)
             || world.run(player_sees_foes)
         {
@@ -871,9 +874,8 @@ pub fn player_input(world: &World, inputs: &mut InputBuffer) -> PlayerInputResul
         }
     } else if let Some(InputEvent::AppQuit) = inputs.get_input() {
         PlayerInputResult::AppQuit
-    } else if let Some(InputEvent::Press(keycode)) = inputs.get_input() {
+    } else if let Some(key) = gamekey::from_input(world, inputs) {
         let shift = inputs.get_mods(KeyMods::SHIFT);
-        let key = gamekey::from_keycode(keycode, shift);
 
         if !matches!(key, GameKey::Unmapped) {
             world
@@ -917,6 +919,8 @@ pub fn player_is_dead_input(inputs: &mut InputBuffer) -> bool {
 
     matches!(
         inputs.get_input(),
-        Some(InputEvent::Press(Keycode::Space)) | Some(InputEvent::AppQuit)
+        Some(InputEvent::Press(Keycode::Space))
+            | Some(InputEvent::ControllerPress(Button::A))
+            | Some(InputEvent::AppQuit)
     )
 }
@@ -1,10 +1,11 @@
-use shipyard::{Get, IntoIter, UniqueView, View, World};
+use shipyard::{Get, IntoIter, IntoWithId, UniqueView, View, World};
 
 use crate::{
     chunked::ChunkedMapGrid,
-    components::{Coord, FieldOfView, RenderOnFloor, RenderOnMap, Renderable},
+    components::{Coord, FieldOfView, Monster, RenderOnFloor, RenderOnMap, Renderable},
     gamesym::GameSym,
     player::PlayerId,
+    vision::LightMap,
 };
 use ruggrogue::TileGrid;
 
@@ -13,11 +14,22 @@ pub fn draw_renderables(
     world: &World,
     grid: &mut TileGrid<GameSym>,
 ) {
-    let (player_id, coords, fovs, render_on_floors, render_on_maps, renderables) = world
+    let (
+        light_map,
+        player_id,
+        coords,
+        fovs,
+        monsters,
+        render_on_floors,
+        render_on_maps,
+        renderables,
+    ) = world
         .borrow::<(
+            UniqueView<LightMap>,
             UniqueView<PlayerId>,
             View<Coord>,
             View<FieldOfView>,
+            View<Monster>,
             View<RenderOnFloor>,
             View<RenderOnMap>,
             View<Renderable>,
@@ -35,9 +47,9 @@ pub fn draw_renderables(
         }
     }
 
-    // Draw normal map entities.
-    for (coord, render, _) in (&coords, &renderables, &render_on_maps).iter() {
-        if fov.get(coord.0.into()) {
+    // Draw normal map entities, except for monsters lurking in the dark.
+    for (id, (coord, render, _)) in (&coords, &renderables, &render_on_maps).iter().with_id() {
+        if fov.get(coord.0.into()) && (!monsters.contains(id) || light_map.is_lit(coord.0.into())) {
             if let Some(pos) = chunked_map_grid.map_to_grid_pos(world, coord.0) {
                 grid.put_sym_color(pos, render.sym, render.fg, render.bg);
             }
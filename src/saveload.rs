@@ -1,4 +1,4 @@
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::{Deserializer, Serializer};
 use shipyard::{
     track::Tracking, AllStoragesViewMut, Component, EntitiesView, EntitiesViewMut, EntityId, Get,
@@ -6,114 +6,601 @@ use shipyard::{
 };
 use std::{
     collections::{hash_map::HashMap, hash_set::HashSet},
-    error, fmt,
+    error,
+    ffi::OsStr,
+    fmt,
     fs::{self, File},
-    io::{BufRead, BufReader, BufWriter, Write},
-    path::Path,
+    io::{BufRead, BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
 };
 
 #[cfg(target_os = "emscripten")]
 use crate::ruggrogue_sync_idbfs;
 use crate::{
     components::*,
+    effects::Effects,
     experience::Difficulty,
     map::Map,
     message::Messages,
     player::{PlayerAlive, PlayerId},
-    spawn, BaseEquipmentLevel, GameSeed, TurnCount, Wins,
+    spawn,
+    ui::{self, Options},
+    BaseEquipmentLevel, GameSeed, TurnCount, Wins,
 };
+use ruggrogue::{Symbol, TilesetInfo};
 
 #[cfg(target_os = "emscripten")]
-const SAVE_FILENAME: &str = "/ruggrogue/savegame.txt";
+const SAVE_DIR: &str = "/ruggrogue/saves";
 
 #[cfg(not(target_os = "emscripten"))]
-const SAVE_FILENAME: &str = "savegame.txt";
+const SAVE_DIR: &str = "saves";
+
+/// Single save file used before save slots existed; moved into the save directory when found.
+#[cfg(target_os = "emscripten")]
+const LEGACY_SAVE_FILENAME: &str = "/ruggrogue/savegame.txt";
+
+#[cfg(not(target_os = "emscripten"))]
+const LEGACY_SAVE_FILENAME: &str = "savegame.txt";
+
+const SAVE_EXTENSION: &str = "sav";
+const TEMP_EXTENSION: &str = "tmp";
+const BACKUP_DIR: &str = "backups";
+
+/// Number of previous saves of each save slot kept as backups.
+const NUM_BACKUPS: usize = 3;
+const DEFAULT_SLOT_NAME: &str = "savegame";
+
+#[cfg(target_os = "emscripten")]
+const OPTIONS_FILENAME: &str = "/ruggrogue/settings.json";
+
+#[cfg(not(target_os = "emscripten"))]
+const OPTIONS_FILENAME: &str = "settings.json";
+
+/// Version of the save file format written by [save_game].  Bump this whenever a change to the
+/// saved uniques or components would stop older saves from loading, and add a matching step to
+/// [MIGRATIONS] that upgrades lines from the previous version.
+const SAVE_FORMAT_VERSION: u32 = 2;
+
+/// Prefix of the line holding the save file format version.
+const VERSION_PREFIX: &str = "version";
+
+/// Bytes that start every binary save file, distinguishing it from a text save file.
+const BINARY_MAGIC: &[u8; 8] = b"RUGGSAV\0";
+
+/// Binary records hold a unique when they start with this byte, otherwise a component.
+const BINARY_UNIQUE_RECORD: u8 = 0;
+const BINARY_COMPONENT_RECORD: u8 = 1;
 
 type BoxedError = Box<dyn error::Error>;
 
+/// The type name and data of a unique or component from a save file, in a form that can be
+/// rewritten by migrations.
+struct SaveLine {
+    type_name: String,
+    data: serde_json::Value,
+}
+
+/// A step that upgrades a save line from one save file format version to the next.
+type Migration = fn(&mut SaveLine);
+
+/// Upgrades for older save files, where the migration at index `n` upgrades lines from version `n`
+/// to version `n + 1`.
+const MIGRATIONS: [Migration; SAVE_FORMAT_VERSION as usize] = [
+    // Version 0 saves predate the version line, but their data is otherwise identical.
+    |_| {},
+    escape_messages,
+];
+
+/// Messages are shown as markup since version 2, so escape any braces in older plain messages.
+fn escape_messages(line: &mut SaveLine) {
+    if line.type_name != "Messages" {
+        return;
+    }
+
+    if let Some(msg_queue) = line
+        .data
+        .get_mut("msg_queue")
+        .and_then(|q| q.as_array_mut())
+    {
+        for msg in msg_queue {
+            if let Some(text) = msg.as_str() {
+                *msg = ruggrogue::escape_markup(text).into();
+            }
+        }
+    }
+}
+
 /// Game-specific errors that can occur when loading a save file.
 #[derive(Debug)]
 pub enum LoadError {
+    BadChecksum,
     DuplicateComponent(usize, &'static str),
     DuplicateUnique(usize, &'static str),
+    MissingHeader,
     MissingUnique(&'static str),
+    TooNew(u32),
     UnknownId(EntityId),
     UnrecognizedLine(usize),
+    UnrecognizedRecord(usize),
 }
 
 impl fmt::Display for LoadError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
         match self {
+            Self::BadChecksum => write!(f, "checksum mismatch, save file is damaged"),
             Self::DuplicateComponent(line_num, which) => {
                 write!(f, "line {}: duplicate {} component", line_num, which)
             }
             Self::DuplicateUnique(line_num, which) => {
                 write!(f, "line {}: duplicate {} unique", line_num, which)
             }
+            Self::MissingHeader => write!(f, "missing save header"),
             Self::MissingUnique(which) => write!(f, "missing {} unique", which),
+            Self::TooNew(version) => write!(
+                f,
+                "save format version {} is newer than supported version {}",
+                version, SAVE_FORMAT_VERSION
+            ),
             Self::UnknownId(id) => write!(f, "unknown entity ID {:?}", *id),
             Self::UnrecognizedLine(line_num) => write!(f, "line {}: unrecognized line", line_num),
+            Self::UnrecognizedRecord(record_num) => {
+                write!(f, "record {}: unrecognized record", record_num)
+            }
         }
     }
 }
 
 impl error::Error for LoadError {}
 
-pub fn save_file_exists() -> bool {
-    Path::new(SAVE_FILENAME).exists()
+/// The name of the save slot that the game in progress is saved to.
+#[derive(Unique)]
+pub struct SaveSlot(pub String);
+
+/// Summary of a save slot written at the start of its save file, so that save slots can be listed
+/// without loading the whole game.
+#[derive(Deserialize, Serialize)]
+pub struct SaveHeader {
+    pub name: String,
+    pub depth: i32,
+    pub level: i32,
+    pub turns: u64,
+    /// Seconds since the Unix epoch when the game was saved.
+    pub saved_at: u64,
+}
+
+impl SaveHeader {
+    fn from_world(world: &World) -> Self {
+        let player_id = world.borrow::<UniqueView<PlayerId>>().unwrap().0;
+
+        Self {
+            name: world
+                .borrow::<View<Name>>()
+                .unwrap()
+                .get(player_id)
+                .map_or_else(|_| String::new(), |n| n.0.clone()),
+            depth: world.borrow::<UniqueView<Map>>().unwrap().depth,
+            level: world
+                .borrow::<View<Experience>>()
+                .unwrap()
+                .get(player_id)
+                .map_or(1, |exp| exp.level),
+            turns: world.borrow::<UniqueView<TurnCount>>().unwrap().0,
+            saved_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+        }
+    }
+
+    /// Read just the header of a save file in either save file format.
+    fn read(path: &Path) -> Result<Self, BoxedError> {
+        let mut reader = BufReader::new(File::open(path)?);
+
+        if reader.fill_buf()?.starts_with(BINARY_MAGIC) {
+            // Skip the magic bytes, version and checksum to reach the header.
+            let mut prefix = [0u8; BINARY_MAGIC.len() + 8];
+            reader.read_exact(&mut prefix)?;
+
+            let mut len = [0u8; 4];
+            reader.read_exact(&mut len)?;
+
+            let mut header = vec![0u8; u32::from_le_bytes(len) as usize];
+            reader.read_exact(&mut header)?;
+
+            Ok(ciborium::de::from_reader(&header[..])?)
+        } else {
+            let mut line = String::new();
+
+            reader.read_line(&mut line)?;
+
+            let header = line.strip_prefix('#').ok_or(LoadError::MissingHeader)?;
+
+            Ok(serde_json::from_str(header.trim())?)
+        }
+    }
+}
+
+/// A save slot found in the save directory, along with its header if it could be read.
+pub struct SaveSlotInfo {
+    pub slot: String,
+    pub header: Option<SaveHeader>,
+}
+
+fn slot_path(slot: &str) -> PathBuf {
+    let slot = if slot.is_empty() {
+        DEFAULT_SLOT_NAME
+    } else {
+        slot
+    };
+
+    Path::new(SAVE_DIR).join(format!("{}.{}", slot, SAVE_EXTENSION))
+}
+
+/// Path of the given backup of a save slot, where backup 1 is the most recent.
+fn backup_path(slot: &str, backup: usize) -> PathBuf {
+    let slot_path = slot_path(slot);
+    let file_name = slot_path.file_stem().unwrap().to_string_lossy();
+
+    Path::new(SAVE_DIR)
+        .join(BACKUP_DIR)
+        .join(format!("{}.{}.{}", file_name, backup, SAVE_EXTENSION))
+}
+
+/// Returns true if a save slot has a save file or any backups, so that a new game doesn't end up
+/// sharing backups with an older one.
+fn slot_in_use(slot: &str) -> bool {
+    slot_path(slot).exists() || (1..=NUM_BACKUPS).any(|b| backup_path(slot, b).exists())
+}
+
+/// Shift the backups of a save slot back by one, dropping the oldest one, and copy the save file
+/// of the slot into the most recent backup.  A save file that can't be read is left out, so that it
+/// doesn't push a good backup out.
+fn rotate_backups(slot: &str) -> Result<(), BoxedError> {
+    let path = slot_path(slot);
+
+    if !path.exists() {
+        return Ok(());
+    }
+
+    if let Err(e) = read_save_records(&path) {
+        eprintln!(
+            "Warning: saveload::rotate_backups: not backing up {}: {}",
+            path.display(),
+            e
+        );
+        return Ok(());
+    }
+
+    fs::create_dir_all(Path::new(SAVE_DIR).join(BACKUP_DIR))?;
+
+    for backup in (1..NUM_BACKUPS).rev() {
+        let older = backup_path(slot, backup);
+
+        if older.exists() {
+            fs::rename(older, backup_path(slot, backup + 1))?;
+        }
+    }
+
+    fs::copy(path, backup_path(slot, 1))?;
+
+    Ok(())
+}
+
+/// Move the save file from before save slots existed into the save directory.  Call this once at
+/// startup before looking for save slots.
+pub fn migrate_legacy_save_file() {
+    if Path::new(LEGACY_SAVE_FILENAME).exists() && !slot_path(DEFAULT_SLOT_NAME).exists() {
+        if let Err(e) = fs::create_dir_all(SAVE_DIR)
+            .and_then(|_| fs::rename(LEGACY_SAVE_FILENAME, slot_path(DEFAULT_SLOT_NAME)))
+        {
+            eprintln!("Warning: saveload::migrate_legacy_save_file: {}", e);
+        }
+    }
+}
+
+/// List all save slots in the save directory, most recently played first.
+pub fn list_save_slots() -> Vec<SaveSlotInfo> {
+    let entries = match fs::read_dir(SAVE_DIR) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+    let mut slots = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && path.extension() == Some(OsStr::new(SAVE_EXTENSION)))
+        .filter_map(|path| {
+            let slot = path.file_stem()?.to_str()?.to_string();
+            let header = SaveHeader::read(&path).ok();
+
+            Some(SaveSlotInfo { slot, header })
+        })
+        .collect::<Vec<_>>();
+
+    slots.sort_by(|a, b| {
+        let a_time = a.header.as_ref().map(|h| h.saved_at);
+        let b_time = b.header.as_ref().map(|h| h.saved_at);
+
+        b_time.cmp(&a_time).then_with(|| a.slot.cmp(&b.slot))
+    });
+
+    slots
+}
+
+pub fn save_slots_exist() -> bool {
+    !list_save_slots().is_empty()
+}
+
+/// Pick a save slot name for a new game based on the player's name that doesn't clash with any
+/// existing save slot, including slots whose games have ended but still have backups.
+pub fn new_slot_name(player_name: &str) -> String {
+    let base = player_name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+    let base = if base.is_empty() {
+        DEFAULT_SLOT_NAME.to_string()
+    } else {
+        base
+    };
+
+    if !slot_in_use(&base) {
+        return base;
+    }
+
+    (2..)
+        .map(|n| format!("{}-{}", base, n))
+        .find(|slot| !slot_in_use(slot))
+        .unwrap()
+}
+
+/// Delete the save file of a save slot along with all of its backups.
+pub fn delete_save_slot(slot: &str) {
+    let paths =
+        std::iter::once(slot_path(slot)).chain((1..=NUM_BACKUPS).map(|b| backup_path(slot, b)));
+
+    for path in paths.filter(|p| p.exists()) {
+        if let Err(e) = fs::remove_file(path) {
+            eprintln!("Warning: saveload::delete_save_slot: {}", e);
+        }
+    }
+
+    #[cfg(target_os = "emscripten")]
+    unsafe {
+        ruggrogue_sync_idbfs();
+    }
+}
+
+/// Retire the save file of a save slot whose game has ended, so that it can no longer be loaded.
+/// The save file is kept as the most recent backup of the slot instead of being deleted outright.
+pub fn retire_save_slot(slot: &str) {
+    let path = slot_path(slot);
+
+    if path.exists() {
+        if let Err(e) = rotate_backups(slot).and_then(|_| Ok(fs::remove_file(path)?)) {
+            eprintln!("Warning: saveload::retire_save_slot: {}", e);
+        }
+
+        #[cfg(target_os = "emscripten")]
+        unsafe {
+            ruggrogue_sync_idbfs();
+        }
+    }
+}
+
+/// Save the options to the settings file.  Unlike the save file, the settings file survives
+/// between games.
+pub fn save_options(world: &World) -> Result<(), BoxedError> {
+    let mut writer = BufWriter::new(File::create(OPTIONS_FILENAME)?);
+
+    serde_json::to_writer_pretty(
+        &mut writer,
+        &*world.borrow::<UniqueView<Options>>().unwrap(),
+    )?;
+    writeln!(writer)?;
+    writer.flush()?;
+
+    #[cfg(target_os = "emscripten")]
+    unsafe {
+        ruggrogue_sync_idbfs();
+    }
+
+    Ok(())
+}
+
+/// Load the options from the settings file.  Missing or out-of-range fields take their default
+/// values and unknown fields are ignored, so settings files from other versions still load.  If
+/// the settings file is absent or can't be read, default options are returned instead.  Tileset
+/// and font choices are checked against the tilesets the game is run with.
+pub fn load_options<Y: Symbol>(tileset_infos: &[TilesetInfo<Y>]) -> Options {
+    if !Path::new(OPTIONS_FILENAME).exists() {
+        return Options::default();
+    }
+
+    let result: Result<Options, BoxedError> = File::open(OPTIONS_FILENAME)
+        .map_err(BoxedError::from)
+        .and_then(|file| serde_json::from_reader(BufReader::new(file)).map_err(BoxedError::from));
+
+    match result {
+        Ok(mut options) => {
+            let defaults = Options::default();
+
+            if options.tileset as usize >= tileset_infos.len() {
+                options.tileset = defaults.tileset;
+            }
+            if options.font >= ui::NUM_FONTS.min(tileset_infos.len() as u32) {
+                options.font = defaults.font;
+            }
+            if !(1..=2).contains(&options.map_zoom) {
+                options.map_zoom = defaults.map_zoom;
+            }
+            if !(1..=2).contains(&options.text_zoom) {
+                options.text_zoom = defaults.text_zoom;
+            }
+
+            options
+        }
+        Err(e) => {
+            eprintln!("Warning: saveload::load_options: {}", e);
+            Options::default()
+        }
+    }
 }
 
-pub fn delete_save_file() {
-    if save_file_exists() {
-        if let Err(e) = fs::remove_file(SAVE_FILENAME) {
-            eprintln!("Warning: saveload::delete_save_file: {}", e);
+/// Formats that save files can be written in.  The loader tells them apart automatically.
+#[derive(Clone, Copy)]
+pub enum SaveFormat {
+    /// One line of JSON per unique or component, which is easy to read and diff.
+    Text,
+    /// Compact CBOR-encoded records after a magic header, with a checksum to detect damage.
+    Binary,
+}
+
+/// Accumulates the header, uniques and components of a save file in a given save file format.
+///
+/// Text saves start with the header as a comment and a version line, then hold a unique per line
+/// as an asterisk, a tab, its type, a tab and its data, and a component per line as an entity ID, a
+/// tab, its type, a tab and its data.
+///
+/// Binary saves start with [BINARY_MAGIC], the format version and a CRC-32 checksum of everything
+/// that follows, which is the length-prefixed header and then the records.  Each record is a
+/// record type byte, an entity ID for components, the length-prefixed type name and the
+/// length-prefixed data.  All lengths and numbers are little-endian.
+struct SaveWriter {
+    format: SaveFormat,
+    buf: Vec<u8>,
+}
+
+impl SaveWriter {
+    fn new(format: SaveFormat, header: &SaveHeader) -> Result<Self, BoxedError> {
+        let mut writer = Self {
+            format,
+            buf: Vec::new(),
+        };
+
+        match format {
+            SaveFormat::Text => {
+                writer.buf.write_all(b"# ")?;
+                header.serialize(&mut Serializer::new(&mut writer.buf))?;
+                writer.buf.write_all(b"\n")?;
+                writeln!(writer.buf, "{}\t{}", VERSION_PREFIX, SAVE_FORMAT_VERSION)?;
+            }
+            SaveFormat::Binary => writer.write_binary_data(header)?,
+        }
+
+        Ok(writer)
+    }
+
+    /// Write length-prefixed CBOR data to a binary save.
+    fn write_binary_data<T: Serialize>(&mut self, data: &T) -> Result<(), BoxedError> {
+        let mut bytes = Vec::new();
+
+        ciborium::ser::into_writer(data, &mut bytes)?;
+        self.buf.write_all(&(bytes.len() as u32).to_le_bytes())?;
+        self.buf.write_all(&bytes)?;
+
+        Ok(())
+    }
+
+    fn write_binary_name(&mut self, name: &str) -> Result<(), BoxedError> {
+        self.buf.write_all(&[name.len() as u8])?;
+        self.buf.write_all(name.as_bytes())?;
+
+        Ok(())
+    }
+
+    fn write_unique<T: Serialize>(&mut self, name: &str, data: &T) -> Result<(), BoxedError> {
+        match self.format {
+            SaveFormat::Text => {
+                write!(self.buf, "*\t{}\t", name)?;
+                data.serialize(&mut Serializer::new(&mut self.buf))?;
+                self.buf.write_all(b"\n")?;
+            }
+            SaveFormat::Binary => {
+                self.buf.write_all(&[BINARY_UNIQUE_RECORD])?;
+                self.write_binary_name(name)?;
+                self.write_binary_data(data)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn write_component<T: Serialize>(
+        &mut self,
+        id: EntityId,
+        name: &str,
+        data: &T,
+    ) -> Result<(), BoxedError> {
+        match self.format {
+            SaveFormat::Text => {
+                id.serialize(&mut Serializer::new(&mut self.buf))?;
+                write!(self.buf, "\t{}\t", name)?;
+                data.serialize(&mut Serializer::new(&mut self.buf))?;
+                self.buf.write_all(b"\n")?;
+            }
+            SaveFormat::Binary => {
+                self.buf.write_all(&[BINARY_COMPONENT_RECORD])?;
+                self.write_binary_data(&id)?;
+                self.write_binary_name(name)?;
+                self.write_binary_data(data)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get the bytes of the complete save file.
+    fn finish(self) -> Vec<u8> {
+        match self.format {
+            SaveFormat::Text => self.buf,
+            SaveFormat::Binary => {
+                let mut bytes = Vec::with_capacity(BINARY_MAGIC.len() + 8 + self.buf.len());
+
+                bytes.extend_from_slice(BINARY_MAGIC);
+                bytes.extend_from_slice(&SAVE_FORMAT_VERSION.to_le_bytes());
+                bytes.extend_from_slice(&crc32fast::hash(&self.buf).to_le_bytes());
+                bytes.extend_from_slice(&self.buf);
+                bytes
+            }
         }
     }
 }
 
-/// Save a unique as an asterisk, a tab, its type, a tab and its serialized data in a single line.
-fn save_named_unique<W, Track, T>(
+fn save_named_unique<Track, T>(
     world: &World,
-    mut writer: &mut W,
+    writer: &mut SaveWriter,
     name: &str,
 ) -> Result<(), BoxedError>
 where
     Track: Tracking + Send + Sync,
     T: 'static + Send + Sync + Serialize + Unique<Tracking = Track>,
-    W: Write,
 {
-    write!(writer, "*\t{}\t", name)?;
-    world
-        .borrow::<UniqueView<T>>()
-        .unwrap()
-        .serialize(&mut Serializer::new(&mut writer))?;
-    writer.write_all(b"\n")?;
-    Ok(())
+    writer.write_unique(name, &*world.borrow::<UniqueView<T>>().unwrap())
 }
 
 macro_rules! save_unique {
     ($type:ty, $world:expr, $writer:expr) => {
-        save_named_unique::<_, _, $type>($world, $writer, stringify!($type))
+        save_named_unique::<_, $type>($world, $writer, stringify!($type))
     };
 }
 
-/// Save components of a storage as an entity ID, a tab, its type, a tab and its serialized data,
-/// one per line.
-fn save_named_storage<W, Track, T>(
+fn save_named_storage<Track, T>(
     world: &World,
-    mut writer: &mut W,
+    writer: &mut SaveWriter,
     name: &str,
 ) -> Result<(), BoxedError>
 where
     Track: Tracking + Send + Sync,
     T: 'static + Send + Sync + Serialize + Component<Tracking = Track>,
-    W: Write,
 {
     for (id, component) in world.borrow::<View<T>>().unwrap().iter().with_id() {
-        id.serialize(&mut Serializer::new(&mut writer))?;
-        write!(writer, "\t{}\t", name)?;
-        component.serialize(&mut Serializer::new(&mut writer))?;
-        writer.write_all(b"\n")?;
+        writer.write_component(id, name, component)?;
     }
 
     Ok(())
@@ -121,13 +608,19 @@ where
 
 macro_rules! save_storage {
     ($type:ty, $world:expr, $writer:expr) => {
-        save_named_storage::<_, _, $type>($world, $writer, stringify!($type))
+        save_named_storage::<_, $type>($world, $writer, stringify!($type))
     };
 }
 
-/// Save all data in uniques and component storages to the save file.
+/// Save all data in uniques and component storages to the file of the current save slot, in the
+/// binary save file format unless text saves are turned on in the options.
 pub fn save_game(world: &World) -> Result<(), BoxedError> {
-    let mut writer = BufWriter::new(File::create(SAVE_FILENAME)?);
+    let format = if world.borrow::<UniqueView<Options>>().unwrap().text_saves {
+        SaveFormat::Text
+    } else {
+        SaveFormat::Binary
+    };
+    let mut writer = SaveWriter::new(format, &SaveHeader::from_world(world))?;
 
     save_unique!(GameSeed, world, &mut writer)?;
     save_unique!(TurnCount, world, &mut writer)?;
@@ -155,6 +648,7 @@ pub fn save_game(world: &World) -> Result<(), BoxedError> {
     save_storage!(InflictsSleep, world, &mut writer)?;
     save_storage!(Inventory, world, &mut writer)?;
     save_storage!(Item, world, &mut writer)?;
+    save_storage!(LightSource, world, &mut writer)?;
     save_storage!(Monster, world, &mut writer)?;
     save_storage!(Name, world, &mut writer)?;
     save_storage!(Nutrition, world, &mut writer)?;
@@ -168,7 +662,27 @@ pub fn save_game(world: &World) -> Result<(), BoxedError> {
     save_storage!(Tally, world, &mut writer)?;
     save_storage!(Victory, world, &mut writer)?;
 
-    writer.flush()?;
+    fs::create_dir_all(SAVE_DIR)?;
+
+    // Write the save to a temporary file first, so that a failed write can't damage the existing
+    // save file, then back up the existing save file and move the new one into its place.
+    let slot = &world.borrow::<UniqueView<SaveSlot>>().unwrap().0;
+    let path = slot_path(slot);
+    let temp_path = path.with_extension(TEMP_EXTENSION);
+    let mut file = BufWriter::new(File::create(&temp_path)?);
+
+    file.write_all(&writer.finish())?;
+    file.flush()?;
+    file.get_ref().sync_all()?;
+    drop(file);
+
+    if let Err(e) = rotate_backups(slot) {
+        eprintln!(
+            "Warning: saveload::save_game: failed to back up save: {}",
+            e
+        );
+    }
+    fs::rename(temp_path, path)?;
 
     #[cfg(target_os = "emscripten")]
     unsafe {
@@ -178,41 +692,77 @@ pub fn save_game(world: &World) -> Result<(), BoxedError> {
     Ok(())
 }
 
-/// Attempt to deserialize a unique of the given named type from a line, inserting the data into
+/// Serialized data of a unique or component read from a save file.
+enum RecordData {
+    Json(String),
+    Cbor(Vec<u8>),
+}
+
+impl RecordData {
+    /// Deserialize the data as the given type, requiring all of it to be used.
+    fn parse<T: DeserializeOwned>(&self) -> Option<T> {
+        match self {
+            Self::Json(json) => {
+                let mut ds = Deserializer::from_str(json);
+                let parsed = T::deserialize(&mut ds).ok()?;
+
+                ds.end().ok().map(|_| parsed)
+            }
+            Self::Cbor(cbor) => {
+                let mut reader = &cbor[..];
+                let parsed = ciborium::de::from_reader(&mut reader).ok()?;
+
+                if reader.is_empty() {
+                    Some(parsed)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    fn into_value(self) -> Result<serde_json::Value, BoxedError> {
+        match self {
+            Self::Json(json) => Ok(serde_json::from_str(&json)?),
+            Self::Cbor(cbor) => Ok(ciborium::de::from_reader(&cbor[..])?),
+        }
+    }
+}
+
+/// A unique or component read from a save file, before being checked against the known types.
+struct SaveRecord {
+    /// Line number of text saves or record number of binary saves, for error messages.
+    num: usize,
+    /// Entity that a component belongs to, or `None` for a unique.
+    id: Option<EntityId>,
+    type_name: String,
+    data: RecordData,
+}
+
+/// Attempt to deserialize a unique of the given named type from a record, inserting the data into
 /// the given `dest` on success.
 ///
-/// Returns `Ok(true)` if the line was successfully parsed, `Ok(false)` if the line wasn't parsed
-/// but might be something else, and `Err` if the line was parsed as a duplicate of a unique that
-/// was already present in `dest`.
-fn deserialize_named_unique<'a, T>(
-    line: &'a str,
-    line_num: usize,
+/// Returns `Ok(true)` if the record was successfully parsed, `Ok(false)` if the record wasn't
+/// parsed but might be something else, and `Err` if the record was parsed as a duplicate of a
+/// unique that was already present in `dest`.
+fn deserialize_named_unique<T>(
+    record: &SaveRecord,
     dest: &mut Option<T>,
     name: &'static str,
 ) -> Result<bool, LoadError>
 where
-    T: Deserialize<'a>,
+    T: DeserializeOwned,
 {
-    let line = if let Some(unprefixed) = line
-        .strip_prefix(name)
-        .and_then(|s| s.strip_prefix(char::is_whitespace))
-    {
-        unprefixed.trim_start()
-    } else {
+    if record.type_name != name {
         return Ok(false);
-    };
-    let mut ds = Deserializer::from_str(line);
+    }
 
-    if let Ok(parsed) = T::deserialize(&mut ds) {
-        if ds.end().is_ok() {
-            if dest.is_none() {
-                *dest = Some(parsed);
-                Ok(true)
-            } else {
-                Err(LoadError::DuplicateUnique(line_num, name))
-            }
+    if let Some(parsed) = record.data.parse() {
+        if dest.is_none() {
+            *dest = Some(parsed);
+            Ok(true)
         } else {
-            Ok(false)
+            Err(LoadError::DuplicateUnique(record.num, name))
         }
     } else {
         Ok(false)
@@ -220,51 +770,40 @@ where
 }
 
 macro_rules! deserialize_unique {
-    ($type:ty, $line:expr, $line_num:expr, $dest:expr) => {
-        deserialize_named_unique::<$type>($line, $line_num, $dest, stringify!($type))
+    ($type:ty, $record:expr, $dest:expr) => {
+        deserialize_named_unique::<$type>($record, $dest, stringify!($type))
     };
 }
 
-/// Attempt to deserialize component data of a given named type from part of a line, adding the
+/// Attempt to deserialize component data of a given named type from a record, adding the
 /// component to the entity with the given `id` on success.
 ///
 /// Returns `Ok(true)` if the component was successfully parsed, `Ok(false)` if the data wasn't
 /// parsed but might be something else, and `Err` if the data was parsed as a duplicate of a
 /// component that the entity already had.
-fn deserialize_named_component<'a, Track, T>(
+fn deserialize_named_component<Track, T>(
     world: &World,
-    maybe_data: &'a str,
-    line_num: usize,
+    record: &SaveRecord,
     id: EntityId,
     name: &'static str,
 ) -> Result<bool, LoadError>
 where
     Track: Tracking + Send + Sync,
-    T: 'static + Send + Sync + Deserialize<'a> + Component<Tracking = Track>,
+    T: 'static + Send + Sync + DeserializeOwned + Component<Tracking = Track>,
 {
-    let maybe_data = if let Some(unprefixed) = maybe_data
-        .strip_prefix(name)
-        .and_then(|s| s.strip_prefix(char::is_whitespace))
-    {
-        unprefixed.trim_start()
-    } else {
+    if record.type_name != name {
         return Ok(false);
-    };
-    let mut ds = Deserializer::from_str(maybe_data);
+    }
 
-    if let Ok(parsed) = T::deserialize(&mut ds) {
-        if ds.end().is_ok() {
-            let entities = world.borrow::<EntitiesView>().unwrap();
-            let mut storage = world.borrow::<ViewMut<T>>().unwrap();
+    if let Some(parsed) = record.data.parse() {
+        let entities = world.borrow::<EntitiesView>().unwrap();
+        let mut storage = world.borrow::<ViewMut<T>>().unwrap();
 
-            if !storage.contains(id) {
-                entities.add_component(id, &mut storage, parsed);
-                Ok(true)
-            } else {
-                Err(LoadError::DuplicateComponent(line_num, name))
-            }
+        if !storage.contains(id) {
+            entities.add_component(id, &mut storage, parsed);
+            Ok(true)
         } else {
-            Ok(false)
+            Err(LoadError::DuplicateComponent(record.num, name))
         }
     } else {
         Ok(false)
@@ -272,29 +811,197 @@ where
 }
 
 macro_rules! deserialize_component {
-    ($type:ty, $world:expr, $maybe_data:expr, $line_num:expr, $id:expr) => {
-        deserialize_named_component::<_, $type>(
-            $world,
-            $maybe_data,
-            $line_num,
-            $id,
-            stringify!($type),
-        )
+    ($type:ty, $world:expr, $record:expr, $id:expr) => {
+        deserialize_named_component::<_, $type>($world, $record, $id, stringify!($type))
     };
 }
 
+/// Upgrade a record from a save file with the given format version to the current format version
+/// by running it through each migration in turn.
+fn migrate_record(record: SaveRecord, version: u32) -> Result<SaveRecord, BoxedError> {
+    if version >= SAVE_FORMAT_VERSION {
+        return Ok(record);
+    }
+
+    let mut save_line = SaveLine {
+        type_name: record.type_name,
+        data: record.data.into_value()?,
+    };
+
+    for migration in &MIGRATIONS[version as usize..] {
+        migration(&mut save_line);
+    }
+
+    Ok(SaveRecord {
+        num: record.num,
+        id: record.id,
+        type_name: save_line.type_name,
+        data: RecordData::Json(serde_json::to_string(&save_line.data)?),
+    })
+}
+
+/// Read the records of a text save file, migrating them to the current format version.
+fn read_text_records(bytes: &[u8]) -> Result<Vec<SaveRecord>, BoxedError> {
+    let mut records = Vec::new();
+    // Saves from before the version line was added count as version 0.
+    let mut version = 0;
+
+    for (line_num, line_bytes) in bytes.lines().enumerate() {
+        let line_num = line_num + 1;
+        let line = line_bytes?;
+
+        // The save header is only needed to list save slots.
+        if line.starts_with('#') {
+            continue;
+        }
+
+        // The version line determines the migrations that later lines need.
+        if let Some(maybe_version) = line
+            .strip_prefix(VERSION_PREFIX)
+            .and_then(|s| s.strip_prefix(char::is_whitespace))
+        {
+            version = maybe_version
+                .trim()
+                .parse()
+                .map_err(|_| LoadError::UnrecognizedLine(line_num))?;
+            if version > SAVE_FORMAT_VERSION {
+                return Err(Box::new(LoadError::TooNew(version)));
+            }
+            continue;
+        }
+
+        // A line starting with an asterisk holds data for a unique, while most lines should
+        // contain component data for an entity.
+        let (id, rest) = if let Some(rest) = line
+            .strip_prefix('*')
+            .and_then(|s| s.strip_prefix(char::is_whitespace))
+        {
+            (None, rest)
+        } else if let Some((maybe_id, rest)) = line.split_once(char::is_whitespace) {
+            let id = EntityId::deserialize(&mut Deserializer::from_str(maybe_id))
+                .map_err(|_| LoadError::UnrecognizedLine(line_num))?;
+            (Some(id), rest)
+        } else {
+            return Err(Box::new(LoadError::UnrecognizedLine(line_num)));
+        };
+        let (type_name, data) = rest
+            .trim_start()
+            .split_once(char::is_whitespace)
+            .ok_or(LoadError::UnrecognizedLine(line_num))?;
+
+        records.push(migrate_record(
+            SaveRecord {
+                num: line_num,
+                id,
+                type_name: type_name.to_string(),
+                data: RecordData::Json(data.trim_start().to_string()),
+            },
+            version,
+        )?);
+    }
+
+    Ok(records)
+}
+
+/// Split `len` bytes off the front of `bytes`.
+fn take_bytes<'a>(bytes: &mut &'a [u8], len: usize) -> Option<&'a [u8]> {
+    if bytes.len() >= len {
+        let (taken, rest) = bytes.split_at(len);
+        *bytes = rest;
+        Some(taken)
+    } else {
+        None
+    }
+}
+
+/// Split length-prefixed data off the front of `bytes`.
+fn take_binary_data<'a>(bytes: &mut &'a [u8]) -> Option<&'a [u8]> {
+    let mut len = [0u8; 4];
+
+    len.copy_from_slice(take_bytes(bytes, 4)?);
+    take_bytes(bytes, u32::from_le_bytes(len) as usize)
+}
+
+/// Read the records of a binary save file, verifying its checksum and migrating the records to
+/// the current format version.
+fn read_binary_records(bytes: &[u8]) -> Result<Vec<SaveRecord>, BoxedError> {
+    let mut bytes = &bytes[BINARY_MAGIC.len()..];
+    let mut version = [0u8; 4];
+    let mut checksum = [0u8; 4];
+
+    version.copy_from_slice(take_bytes(&mut bytes, 4).ok_or(LoadError::MissingHeader)?);
+    checksum.copy_from_slice(take_bytes(&mut bytes, 4).ok_or(LoadError::MissingHeader)?);
+
+    let version = u32::from_le_bytes(version);
+    if version > SAVE_FORMAT_VERSION {
+        return Err(Box::new(LoadError::TooNew(version)));
+    }
+    if crc32fast::hash(bytes) != u32::from_le_bytes(checksum) {
+        return Err(Box::new(LoadError::BadChecksum));
+    }
+
+    // The header is only needed to list save slots.
+    take_binary_data(&mut bytes).ok_or(LoadError::MissingHeader)?;
+
+    let mut records = Vec::new();
+
+    while !bytes.is_empty() {
+        let record_num = records.len() + 1;
+        let mut read_record = || -> Option<SaveRecord> {
+            let id = match take_bytes(&mut bytes, 1)?[0] {
+                BINARY_UNIQUE_RECORD => None,
+                BINARY_COMPONENT_RECORD => {
+                    Some(ciborium::de::from_reader(take_binary_data(&mut bytes)?).ok()?)
+                }
+                _ => return None,
+            };
+            let name_len = take_bytes(&mut bytes, 1)?[0] as usize;
+            let type_name = std::str::from_utf8(take_bytes(&mut bytes, name_len)?).ok()?;
+            let data = take_binary_data(&mut bytes)?;
+
+            Some(SaveRecord {
+                num: record_num,
+                id,
+                type_name: type_name.to_string(),
+                data: RecordData::Cbor(data.to_vec()),
+            })
+        };
+        let record = read_record().ok_or(LoadError::UnrecognizedRecord(record_num))?;
+
+        records.push(migrate_record(record, version)?);
+    }
+
+    Ok(records)
+}
+
+/// Read the records of a save file, detecting whether it's a text or binary save file.
+fn read_save_records(path: &Path) -> Result<Vec<SaveRecord>, BoxedError> {
+    let bytes = fs::read(path)?;
+
+    if bytes.starts_with(BINARY_MAGIC) {
+        read_binary_records(&bytes)
+    } else {
+        read_text_records(&bytes)
+    }
+}
+
 /// Load data out of the save file, with a list of entities to be despawned afterwards.
 ///
-/// Loading saved data involves loading and interpreting data line by line; uniques are loaded to
+/// Loading saved data involves loading and interpreting data record by record; uniques are loaded to
 /// temporary space, while components are added to the entities in the world that are created if
 /// they don't yet exist.  After some basic validation, the unique data is committed to the world,
 /// and old entities are marked for despawning.
 ///
-/// Loaded entities are created on a line-by-line basis, but loading can fail in a lot of ways,
+/// Loaded entities are created on a record-by-record basis, but loading can fail in a lot of ways,
 /// which would normally leave a partially-loaded group of entities behind.  To prevent this,
 /// freshly-created entities are added to the `despawn_ids` list, which is only cleared and
 /// replaced with old existing entities after final validation of the loaded data.
-fn load_save_file(world: &World, despawn_ids: &mut Vec<EntityId>) -> Result<(), BoxedError> {
+fn load_save_file(
+    world: &World,
+    slot: &str,
+    path: &Path,
+    despawn_ids: &mut Vec<EntityId>,
+) -> Result<(), BoxedError> {
     let mut game_seed: Option<GameSeed> = None;
     let mut turn_count: Option<TurnCount> = None;
     let mut wins: Option<Wins> = None;
@@ -305,43 +1012,11 @@ fn load_save_file(world: &World, despawn_ids: &mut Vec<EntityId>) -> Result<(),
     let mut player_id: Option<PlayerId> = None;
     let mut map: Option<Map> = None;
     let mut old_to_new_ids: HashMap<EntityId, EntityId> = HashMap::new();
-    let reader = BufReader::new(File::open(SAVE_FILENAME)?);
-
-    for (line_num, line_bytes) in reader.lines().enumerate() {
-        let line_num = line_num + 1;
-        let line = line_bytes?;
-
-        // A line starting with an asterisk should hold data for a unique.
-        if let Some(maybe_unique) = line
-            .strip_prefix('*')
-            .and_then(|s| s.strip_prefix(char::is_whitespace))
-        {
-            let maybe_unique = maybe_unique.trim_start();
-
-            // Try parsing the line as a unique.
-            if deserialize_unique!(GameSeed, maybe_unique, line_num, &mut game_seed)?
-                || deserialize_unique!(TurnCount, maybe_unique, line_num, &mut turn_count)?
-                || deserialize_unique!(Wins, maybe_unique, line_num, &mut wins)?
-                || deserialize_unique!(
-                    BaseEquipmentLevel,
-                    maybe_unique,
-                    line_num,
-                    &mut base_equipment_level
-                )?
-                || deserialize_unique!(Difficulty, maybe_unique, line_num, &mut difficulty)?
-                || deserialize_unique!(Messages, maybe_unique, line_num, &mut messages)?
-                || deserialize_unique!(PlayerAlive, maybe_unique, line_num, &mut player_alive)?
-                || deserialize_unique!(PlayerId, maybe_unique, line_num, &mut player_id)?
-                || deserialize_unique!(Map, maybe_unique, line_num, &mut map)?
-            {
-                continue;
-            }
-        }
 
-        // Most lines should contain component data for an entity.
-        if let Some((maybe_id, maybe_data)) = line.split_once(char::is_whitespace) {
-            let save_id = EntityId::deserialize(&mut Deserializer::from_str(maybe_id))?;
+    for record in read_save_records(path)? {
+        let record = &record;
 
+        if let Some(save_id) = record.id {
             // Map entity_id into the current world, creating a new entity if needed.
             let live_id = if let Some(id) = old_to_new_ids.get(&save_id) {
                 *id
@@ -356,46 +1031,57 @@ fn load_save_file(world: &World, despawn_ids: &mut Vec<EntityId>) -> Result<(),
                 new_id
             };
 
-            let maybe_data = maybe_data.trim_start();
-
-            // Try parsing maybe_data and add it to the entity on success.
-            if deserialize_component!(AreaOfEffect, world, maybe_data, line_num, live_id)?
-                || deserialize_component!(Asleep, world, maybe_data, line_num, live_id)?
-                || deserialize_component!(BlocksTile, world, maybe_data, line_num, live_id)?
-                || deserialize_component!(CombatBonus, world, maybe_data, line_num, live_id)?
-                || deserialize_component!(CombatStats, world, maybe_data, line_num, live_id)?
-                || deserialize_component!(Consumable, world, maybe_data, line_num, live_id)?
-                || deserialize_component!(Coord, world, maybe_data, line_num, live_id)?
-                || deserialize_component!(EquipSlot, world, maybe_data, line_num, live_id)?
-                || deserialize_component!(Equipment, world, maybe_data, line_num, live_id)?
-                || deserialize_component!(Experience, world, maybe_data, line_num, live_id)?
-                || deserialize_component!(FieldOfView, world, maybe_data, line_num, live_id)?
-                || deserialize_component!(GivesExperience, world, maybe_data, line_num, live_id)?
-                || deserialize_component!(InflictsDamage, world, maybe_data, line_num, live_id)?
-                || deserialize_component!(InflictsSleep, world, maybe_data, line_num, live_id)?
-                || deserialize_component!(Inventory, world, maybe_data, line_num, live_id)?
-                || deserialize_component!(Item, world, maybe_data, line_num, live_id)?
-                || deserialize_component!(Monster, world, maybe_data, line_num, live_id)?
-                || deserialize_component!(Name, world, maybe_data, line_num, live_id)?
-                || deserialize_component!(Nutrition, world, maybe_data, line_num, live_id)?
-                || deserialize_component!(Player, world, maybe_data, line_num, live_id)?
-                || deserialize_component!(ProvidesHealing, world, maybe_data, line_num, live_id)?
-                || deserialize_component!(Ranged, world, maybe_data, line_num, live_id)?
-                || deserialize_component!(RenderOnFloor, world, maybe_data, line_num, live_id)?
-                || deserialize_component!(RenderOnMap, world, maybe_data, line_num, live_id)?
-                || deserialize_component!(Renderable, world, maybe_data, line_num, live_id)?
-                || deserialize_component!(Stomach, world, maybe_data, line_num, live_id)?
-                || deserialize_component!(Tally, world, maybe_data, line_num, live_id)?
-                || deserialize_component!(Victory, world, maybe_data, line_num, live_id)?
+            // Try parsing the record and add it to the entity on success.
+            if deserialize_component!(AreaOfEffect, world, record, live_id)?
+                || deserialize_component!(Asleep, world, record, live_id)?
+                || deserialize_component!(BlocksTile, world, record, live_id)?
+                || deserialize_component!(CombatBonus, world, record, live_id)?
+                || deserialize_component!(CombatStats, world, record, live_id)?
+                || deserialize_component!(Consumable, world, record, live_id)?
+                || deserialize_component!(Coord, world, record, live_id)?
+                || deserialize_component!(EquipSlot, world, record, live_id)?
+                || deserialize_component!(Equipment, world, record, live_id)?
+                || deserialize_component!(Experience, world, record, live_id)?
+                || deserialize_component!(FieldOfView, world, record, live_id)?
+                || deserialize_component!(GivesExperience, world, record, live_id)?
+                || deserialize_component!(InflictsDamage, world, record, live_id)?
+                || deserialize_component!(InflictsSleep, world, record, live_id)?
+                || deserialize_component!(Inventory, world, record, live_id)?
+                || deserialize_component!(Item, world, record, live_id)?
+                || deserialize_component!(LightSource, world, record, live_id)?
+                || deserialize_component!(Monster, world, record, live_id)?
+                || deserialize_component!(Name, world, record, live_id)?
+                || deserialize_component!(Nutrition, world, record, live_id)?
+                || deserialize_component!(Player, world, record, live_id)?
+                || deserialize_component!(ProvidesHealing, world, record, live_id)?
+                || deserialize_component!(Ranged, world, record, live_id)?
+                || deserialize_component!(RenderOnFloor, world, record, live_id)?
+                || deserialize_component!(RenderOnMap, world, record, live_id)?
+                || deserialize_component!(Renderable, world, record, live_id)?
+                || deserialize_component!(Stomach, world, record, live_id)?
+                || deserialize_component!(Tally, world, record, live_id)?
+                || deserialize_component!(Victory, world, record, live_id)?
             {
                 continue;
             }
-
-            // No other kinds of lines are valid.
-            return Err(Box::new(LoadError::UnrecognizedLine(line_num)));
+        } else if deserialize_unique!(GameSeed, record, &mut game_seed)?
+            || deserialize_unique!(TurnCount, record, &mut turn_count)?
+            || deserialize_unique!(Wins, record, &mut wins)?
+            || deserialize_unique!(BaseEquipmentLevel, record, &mut base_equipment_level)?
+            || deserialize_unique!(Difficulty, record, &mut difficulty)?
+            || deserialize_unique!(Messages, record, &mut messages)?
+            || deserialize_unique!(PlayerAlive, record, &mut player_alive)?
+            || deserialize_unique!(PlayerId, record, &mut player_id)?
+            || deserialize_unique!(Map, record, &mut map)?
+        {
+            continue;
         }
 
-        return Err(Box::new(LoadError::UnrecognizedLine(line_num)));
+        // No other kinds of records are valid.
+        return Err(Box::new(match record.data {
+            RecordData::Json(_) => LoadError::UnrecognizedLine(record.num),
+            RecordData::Cbor(_) => LoadError::UnrecognizedRecord(record.num),
+        }));
     }
 
     // Check that all uniques are present.
@@ -458,6 +1144,19 @@ fn load_save_file(world: &World, despawn_ids: &mut Vec<EntityId>) -> Result<(),
         }
     }
 
+    // Give the player the torch that saves from before light sources were added lack.
+    if !world
+        .borrow::<View<LightSource>>()
+        .unwrap()
+        .contains(player_id.0)
+    {
+        world.borrow::<EntitiesViewMut>().unwrap().add_component(
+            player_id.0,
+            &mut world.borrow::<ViewMut<LightSource>>().unwrap(),
+            spawn::PLAYER_LIGHT,
+        );
+    }
+
     // Place all Coord-carrying entities on the map.
     for (id, coord) in IntoIter::iter(&world.borrow::<View<Coord>>().unwrap()).with_id() {
         let blocks_tile = world.borrow::<View<BlocksTile>>().unwrap().get(id).is_ok();
@@ -485,18 +1184,20 @@ fn load_save_file(world: &World, despawn_ids: &mut Vec<EntityId>) -> Result<(),
         .borrow::<UniqueViewMut<Messages>>()
         .unwrap()
         .replace(messages);
+    world.borrow::<UniqueViewMut<Effects>>().unwrap().clear();
     world.borrow::<UniqueViewMut<PlayerAlive>>().unwrap().0 = player_alive.0;
     world.borrow::<UniqueViewMut<PlayerId>>().unwrap().0 = player_id.0;
     world.borrow::<UniqueViewMut<Map>>().unwrap().replace(map);
+    world.borrow::<UniqueViewMut<SaveSlot>>().unwrap().0 = slot.to_string();
 
     Ok(())
 }
 
-/// Load the game state stored in the save file and despawn entities that need despawning after the
-/// process of loading succeeds or fails.
-pub fn load_game(world: &World) -> Result<(), BoxedError> {
+/// Load the game state stored in the given save file and despawn entities that need despawning
+/// after the process of loading succeeds or fails.
+fn load_game_from(world: &World, slot: &str, path: &Path) -> Result<(), BoxedError> {
     let mut delete_ids = Vec::new();
-    let result = load_save_file(world, &mut delete_ids);
+    let result = load_save_file(world, slot, path, &mut delete_ids);
 
     for id in delete_ids {
         spawn::despawn_entity(&mut world.borrow::<AllStoragesViewMut>().unwrap(), id);
@@ -505,6 +1206,42 @@ pub fn load_game(world: &World) -> Result<(), BoxedError> {
     result
 }
 
+/// Load the game state stored in the given save slot.  If the save file of the slot fails to load,
+/// its backups are tried from newest to oldest, and the error of the save file is only returned if
+/// none of them load either.  A backup that loads replaces the broken save file.
+pub fn load_game(world: &World, slot: &str) -> Result<(), BoxedError> {
+    let path = slot_path(slot);
+    let result = load_game_from(world, slot, &path);
+
+    if let Err(e) = &result {
+        for backup in (1..=NUM_BACKUPS).map(|b| backup_path(slot, b)) {
+            if backup.exists() && load_game_from(world, slot, &backup).is_ok() {
+                eprintln!(
+                    "Warning: saveload::load_game: {}; loaded backup {} instead",
+                    e,
+                    backup.display()
+                );
+
+                if let Err(e) = fs::copy(&backup, &path) {
+                    eprintln!(
+                        "Warning: saveload::load_game: failed to restore backup: {}",
+                        e
+                    );
+                }
+
+                #[cfg(target_os = "emscripten")]
+                unsafe {
+                    ruggrogue_sync_idbfs();
+                }
+
+                return Ok(());
+            }
+        }
+    }
+
+    result
+}
+
 /// Helper module that converts a list of values into a run-length encoded vector of pairs when
 /// serializing and deserializing it with Serde.
 pub mod run_length_encoded {
@@ -574,3 +1311,60 @@ pub mod bit_vec {
             .collect::<BitVec>())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slot_paths_keep_dots_in_slot_names() {
+        assert_eq!(
+            slot_path("v1.2"),
+            Path::new(SAVE_DIR).join(format!("v1.2.{}", SAVE_EXTENSION))
+        );
+        assert_eq!(slot_path(""), slot_path(DEFAULT_SLOT_NAME));
+    }
+
+    #[test]
+    fn backup_paths_are_distinct() {
+        let backup_dir = Path::new(SAVE_DIR).join(BACKUP_DIR);
+
+        assert_eq!(
+            backup_path("v1.2", 1),
+            backup_dir.join(format!("v1.2.1.{}", SAVE_EXTENSION))
+        );
+        assert_ne!(backup_path("hero", 1), backup_path("hero", 2));
+        assert_ne!(backup_path("hero", 1), slot_path("hero"));
+    }
+
+    #[test]
+    fn messages_are_escaped_when_upgrading_to_markup() {
+        let mut line = SaveLine {
+            type_name: "Messages".to_string(),
+            data: serde_json::json!({
+                "capacity": 4,
+                "msg_queue": ["You see {brace}.", "", "Plain."],
+                "num_highlighted": 1,
+                "want_separator": false,
+            }),
+        };
+
+        for migration in &MIGRATIONS[1..] {
+            migration(&mut line);
+        }
+
+        assert_eq!(
+            line.data["msg_queue"],
+            serde_json::json!(["You see {{brace}.", "", "Plain."])
+        );
+        assert_eq!(line.data["num_highlighted"], 1);
+
+        // Other lines are left alone.
+        let mut other = SaveLine {
+            type_name: "Name".to_string(),
+            data: serde_json::json!({ "msg_queue": ["{x}"] }),
+        };
+        escape_messages(&mut other);
+        assert_eq!(other.data["msg_queue"], serde_json::json!(["{x}"]));
+    }
+}
@@ -0,0 +1,90 @@
+use std::{
+    error,
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::gamesym::GameSym;
+#[cfg(target_os = "emscripten")]
+use crate::ruggrogue_sync_idbfs;
+use ruggrogue::{InputBuffer, TileGridLayer};
+
+#[cfg(target_os = "emscripten")]
+const SCREENSHOT_DIR: &str = "/ruggrogue/screenshots";
+
+#[cfg(not(target_os = "emscripten"))]
+const SCREENSHOT_DIR: &str = "screenshots";
+
+/// Create the screenshot directory and pick a new file name in it with the given extension, based
+/// on the current time, e.g. "screenshots/screenshot-1622555100.png".
+fn new_screenshot_path(extension: &str) -> Result<PathBuf, Box<dyn error::Error>> {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let mut path = PathBuf::from(SCREENSHOT_DIR).join(format!("screenshot-{}", secs));
+    let mut n = 1;
+
+    fs::create_dir_all(SCREENSHOT_DIR)?;
+
+    path.set_extension(extension);
+    while path.exists() {
+        n += 1;
+        path = PathBuf::from(SCREENSHOT_DIR).join(format!("screenshot-{}-{}", secs, n));
+        path.set_extension(extension);
+    }
+
+    Ok(path)
+}
+
+/// Ask the main loop to save a PNG screenshot of the next frame it displays, returning the path it
+/// will be saved to.  Pass this to [sync_png] once that frame has been displayed.
+pub fn request_png(inputs: &mut InputBuffer) -> Option<PathBuf> {
+    match new_screenshot_path("png") {
+        Ok(path) => {
+            inputs.request_screenshot(path.clone());
+            Some(path)
+        }
+        Err(e) => {
+            eprintln!("Warning: screenshot::request_png: {}", e);
+            None
+        }
+    }
+}
+
+/// Persist a PNG screenshot saved by the main loop, which can't do it itself in the browser.
+pub fn sync_png(path: &Path) {
+    #[cfg(target_os = "emscripten")]
+    {
+        if path.exists() {
+            unsafe {
+                ruggrogue_sync_idbfs();
+            }
+        }
+    }
+
+    #[cfg(not(target_os = "emscripten"))]
+    let _ = path;
+}
+
+/// Dump the text of the visible grids of the layers to a text file right away.
+pub fn save_text(layers: &[TileGridLayer<GameSym>]) {
+    if let Err(e) = save_text_file(layers) {
+        eprintln!("Warning: screenshot::save_text: {}", e);
+    }
+}
+
+fn save_text_file(layers: &[TileGridLayer<GameSym>]) -> Result<(), Box<dyn error::Error>> {
+    let mut writer = BufWriter::new(File::create(new_screenshot_path("txt")?)?);
+
+    writer.write_all(ruggrogue::layers_to_text(layers).as_bytes())?;
+    writer.flush()?;
+
+    #[cfg(target_os = "emscripten")]
+    unsafe {
+        ruggrogue_sync_idbfs();
+    }
+
+    Ok(())
+}
@@ -22,6 +22,44 @@ use ruggrogue::util::Color;
 
 const EQUIPMENT_SPAWN_PERIOD: u32 = 4;
 
+/// The torch carried by the player.
+pub const PLAYER_LIGHT: LightSource = LightSource {
+    range: 6,
+    intensity: 255,
+};
+
+/// Monsters that glow, lighting up their surroundings.
+const GLOWING_MONSTERS: [(GameSym, LightSource); 4] = [
+    (
+        GameSym::Ghost,
+        LightSource {
+            range: 2,
+            intensity: 128,
+        },
+    ),
+    (
+        GameSym::Warlock,
+        LightSource {
+            range: 3,
+            intensity: 160,
+        },
+    ),
+    (
+        GameSym::Demon,
+        LightSource {
+            range: 3,
+            intensity: 192,
+        },
+    ),
+    (
+        GameSym::Jellybean,
+        LightSource {
+            range: 2,
+            intensity: 128,
+        },
+    ),
+];
+
 const MONSTERS: [(GameSym, &str, (u8, u8, u8)); 25] = [
     (GameSym::Blob, "Blob", (89, 162, 191)),
     (GameSym::Bat, "Bat", (128, 128, 128)),
@@ -101,6 +139,7 @@ pub fn spawn_player(
     mut exps: ViewMut<Experience>,
     mut fovs: ViewMut<FieldOfView>,
     mut inventories: ViewMut<Inventory>,
+    mut light_sources: ViewMut<LightSource>,
     mut names: ViewMut<Name>,
     mut players: ViewMut<Player>,
     (mut render_on_maps, mut renderables, mut stomachs, mut tallies): (
@@ -155,12 +194,13 @@ pub fn spawn_player(
 
     entities.add_component(
         id,
-        (&mut equipments, &mut tallies),
+        (&mut equipments, &mut light_sources, &mut tallies),
         (
             Equipment {
                 weapon: None,
                 armor: None,
             },
+            PLAYER_LIGHT,
             Tally {
                 damage_dealt: 0,
                 damage_taken: 0,
@@ -488,6 +528,17 @@ fn spawn_monster(world: &World, pos: (i32, i32), level: i32, sym: GameSym, name:
         ),
     );
 
+    if let Some((_, light)) = GLOWING_MONSTERS.iter().find(|(s, _)| *s == sym) {
+        world.borrow::<EntitiesViewMut>().unwrap().add_component(
+            monster_id,
+            &mut world.borrow::<ViewMut<LightSource>>().unwrap(),
+            LightSource {
+                range: light.range,
+                intensity: light.intensity,
+            },
+        );
+    }
+
     world
         .borrow::<UniqueViewMut<Map>>()
         .unwrap()
@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use shipyard::{Get, Unique, UniqueView, View, World};
 
 use crate::{
@@ -12,7 +13,7 @@ use crate::{
 };
 use ruggrogue::{
     util::{Color, Position, Size},
-    Symbol, TileGrid, Tileset,
+    Symbol, TileGrid, Tileset, WindowMode,
 };
 
 pub const SELECTED_BG: Color = Color {
@@ -21,12 +22,50 @@ pub const SELECTED_BG: Color = Color {
     b: 255,
 };
 
-#[derive(Unique)]
+/// Color of hit points lost in messages.
+pub const DAMAGE_FG: Color = Color {
+    r: 255,
+    g: 64,
+    b: 64,
+};
+
+/// Color of hit points gained in messages.
+pub const HEAL_FG: Color = Color {
+    r: 64,
+    g: 224,
+    b: 64,
+};
+
+/// Number of tilesets at the start of the tileset list that are fonts, and can thus draw text.
+pub const NUM_FONTS: u32 = 2;
+
+#[derive(Unique, Deserialize, Serialize)]
+#[serde(default)]
 pub struct Options {
     pub tileset: u32,
     pub font: u32,
     pub map_zoom: u32,
     pub text_zoom: u32,
+    pub window_mode: WindowMode,
+    /// Display everything scaled up by the largest whole number that fits the window.
+    pub integer_scale: bool,
+    /// Write saves in the text save format instead of the binary one, to make them easy to read
+    /// and diff.  Only settable in the settings file.
+    pub text_saves: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            tileset: 2,
+            font: 0,
+            map_zoom: 1,
+            text_zoom: 1,
+            window_mode: WindowMode::Windowed,
+            integer_scale: false,
+            text_saves: false,
+        }
+    }
 }
 
 pub const MAP_GRID: usize = 0;
@@ -53,7 +92,7 @@ fn draw_status<Y: Symbol>(world: &World, grid: &mut TileGrid<Y>) {
 
         grid.put_char((2, 0), ' ');
         grid.print((3, 0), player_name);
-        grid.put_char((3 + player_name.len() as i32, 0), ' ');
+        grid.put_char((3 + player_name.chars().count() as i32, 0), ' ');
     }
 
     grid.print_color(
@@ -268,12 +307,12 @@ where
         let msg_fg = if highlighted { highlight_fg } else { fg };
 
         grid.put_char_color((0, y), '>', msg_fg, None);
-        for line in ruggrogue::word_wrap(message, width) {
+        for line in ruggrogue::word_wrap_markup(message, width) {
             if skip_y > 0 {
                 skip_y -= 1;
                 continue;
             }
-            grid.print_color((2, y), line, true, msg_fg, None);
+            grid.print_markup((2, y), &line, true, msg_fg, None);
             y += 1;
             if y > max_y {
                 break;
@@ -1,17 +1,97 @@
-use shipyard::{IntoIter, IntoWithId, UniqueViewMut, View, ViewMut};
+use shipyard::{IntoIter, IntoWithId, Unique, UniqueViewMut, View, ViewMut};
 
 use crate::{
-    components::{Coord, FieldOfView, Player},
+    components::{Coord, FieldOfView, LightSource, Player},
     map::{Map, Tile},
 };
 use ruggrogue::FovShape;
 
+/// Light level below which monsters on a tile can't be made out, even if it's in view.
+pub const DARK: u8 = 64;
+
+/// The light level of every tile of the map, combining the ambient light of the current depth with
+/// the light cast by all light sources.
+#[derive(Unique)]
+pub struct LightMap {
+    width: i32,
+    height: i32,
+    levels: Vec<u8>,
+}
+
+impl LightMap {
+    pub fn new() -> Self {
+        Self {
+            width: 0,
+            height: 0,
+            levels: Vec::new(),
+        }
+    }
+
+    /// Light level of the given map position, where 0 is pitch black and 255 is fully lit.
+    pub fn get(&self, (x, y): (i32, i32)) -> u8 {
+        if x >= 0 && y >= 0 && x < self.width && y < self.height {
+            self.levels[(y * self.width + x) as usize]
+        } else {
+            0
+        }
+    }
+
+    /// Returns true if the given map position is bright enough to make out monsters.
+    pub fn is_lit(&self, pos: (i32, i32)) -> bool {
+        self.get(pos) >= DARK
+    }
+}
+
+/// Light level of tiles far from any light sources, which gets darker the deeper the player goes.
+fn ambient_light(depth: i32) -> u8 {
+    (240 - 24 * depth).clamp(16, 255) as u8
+}
+
+/// Fill the light map with ambient light and add the light cast by each light source, which falls
+/// off with distance from the source.
+fn recalculate_lighting(
+    map: &Map,
+    light_map: &mut LightMap,
+    coords: &View<Coord>,
+    light_sources: &View<LightSource>,
+) {
+    let ambient = ambient_light(map.depth);
+
+    light_map.width = map.width;
+    light_map.height = map.height;
+    light_map.levels.clear();
+    light_map
+        .levels
+        .resize((map.width * map.height) as usize, ambient);
+
+    for (coord, light) in (coords, light_sources).iter() {
+        let (cx, cy) = coord.0.into();
+        let reach2 = ((light.range + 1) * (light.range + 1)) as f32;
+
+        for (x, y, symmetric) in
+            ruggrogue::field_of_view(map, (cx, cy), light.range, FovShape::CirclePlus)
+        {
+            if symmetric || matches!(map.get_tile(x, y), &Tile::Wall) {
+                let dist2 = ((x - cx) * (x - cx) + (y - cy) * (y - cy)) as f32;
+                let amount = (light.intensity as f32 * (1.0 - dist2 / reach2)).max(0.0) as u8;
+                let level = &mut light_map.levels[(y * map.width + x) as usize];
+
+                *level = level.saturating_add(amount);
+            }
+        }
+    }
+}
+
 pub fn recalculate_fields_of_view(
     mut map: UniqueViewMut<Map>,
+    mut light_map: UniqueViewMut<LightMap>,
     coords: View<Coord>,
     mut fovs: ViewMut<FieldOfView>,
+    light_sources: View<LightSource>,
     players: View<Player>,
 ) {
+    recalculate_lighting(&map, &mut light_map, &coords, &light_sources);
+
     for (id, (coord, mut fov)) in (&coords, &mut fovs).iter().with_id() {
         if fov.dirty {
             fov.center = coord.0.into();